@@ -125,7 +125,8 @@ fn build_libarchway(out: PathBuf) {
         .arg("-w")
         .arg("-o")
         .arg(out)
-        .arg("main.go")
+        // Every file of the main package holds exports
+        .arg(".")
         .spawn()
         .unwrap()
         .wait()
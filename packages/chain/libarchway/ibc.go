@@ -0,0 +1,728 @@
+package main
+
+import "C"
+
+import (
+	// std
+	"encoding/json"
+	"fmt"
+	"time"
+
+	// helpers
+	"github.com/pkg/errors"
+
+	// tendermint
+	abci "github.com/cometbft/cometbft/abci/types"
+
+	// cosmos sdk
+	upgradetypes "cosmossdk.io/x/upgrade/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+
+	// ibc
+	clienttypes "github.com/cosmos/ibc-go/v8/modules/core/02-client/types"
+	connectiontypes "github.com/cosmos/ibc-go/v8/modules/core/03-connection/types"
+	channeltypes "github.com/cosmos/ibc-go/v8/modules/core/04-channel/types"
+	commitmenttypes "github.com/cosmos/ibc-go/v8/modules/core/23-commitment/types"
+	host "github.com/cosmos/ibc-go/v8/modules/core/24-host"
+	ibcexported "github.com/cosmos/ibc-go/v8/modules/core/exported"
+	ibctm "github.com/cosmos/ibc-go/v8/modules/light-clients/07-tendermint"
+
+	// cosmwasm-testing
+	"github.com/FloppyDisck/archway-test-tube/result"
+	"github.com/FloppyDisck/archway-test-tube/testenv"
+)
+
+// Chains advance their clocks independently, so the allowed drift between them is generous
+const ibcMaxClockDrift = 365 * 24 * time.Hour
+
+// Every relayed block advances the chain time like an empty block does
+const ibcBlockTime = 5 * time.Second
+
+// ibcPath identifies the clients and connections linking two envs
+type ibcPath struct {
+	ClientA     string `json:"client_a"`
+	ClientB     string `json:"client_b"`
+	ConnectionA string `json:"connection_a"`
+	ConnectionB string `json:"connection_b"`
+}
+
+type relayReport struct {
+	Handshakes uint64 `json:"handshakes"`
+	Packets    uint64 `json:"packets"`
+	Acks       uint64 `json:"acks"`
+	Timeouts   uint64 `json:"timeouts"`
+}
+
+// endpoint is one side of an ibcPath
+type endpoint struct {
+	env          *testenv.TestEnv
+	clientID     string
+	connectionID string
+}
+
+//export IbcCreatePath
+func IbcCreatePath(envIdA, envIdB uint64) *C.char {
+	if envIdA == envIdB {
+		return encodeErrToResultBytes(result.ExecuteError, errors.New("cannot link an env with itself"))
+	}
+
+	envA, envB, unlock, err := loadEnvPair(envIdA, envIdB)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+	// Blocks are committed even if the handshake fails midway
+	defer func() {
+		storeEnv(envIdA, envA)
+		storeEnv(envIdB, envB)
+	}()
+
+	a := endpoint{env: &envA}
+	b := endpoint{env: &envB}
+
+	if a.clientID, err = createClient(a.env, b.env); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, errors.Wrap(err, "create client"))
+	}
+	if b.clientID, err = createClient(b.env, a.env); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, errors.Wrap(err, "create client"))
+	}
+	if err = openConnection(&a, &b); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, errors.Wrap(err, "open connection"))
+	}
+
+	bz, err := json.Marshal(ibcPath{
+		ClientA:     a.clientID,
+		ClientB:     b.clientID,
+		ConnectionA: a.connectionID,
+		ConnectionB: b.connectionID,
+	})
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes(bz)
+}
+
+//export IbcRelay
+func IbcRelay(envIdA, envIdB uint64, pathJson string) *C.char {
+	var path ibcPath
+	if err := json.Unmarshal([]byte(pathJson), &path); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	envA, envB, unlock, err := loadEnvPair(envIdA, envIdB)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+	defer func() {
+		storeEnv(envIdA, envA)
+		storeEnv(envIdB, envB)
+	}()
+
+	a := endpoint{env: &envA, clientID: path.ClientA, connectionID: path.ConnectionA}
+	b := endpoint{env: &envB, clientID: path.ClientB, connectionID: path.ConnectionB}
+
+	report, err := relay(a, b)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	bz, err := json.Marshal(report)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes(bz)
+}
+
+//export IbcOpenChannel
+func IbcOpenChannel(envIdA, envIdB uint64, pathJson, portA, portB, version, order string) *C.char {
+	var path ibcPath
+	if err := json.Unmarshal([]byte(pathJson), &path); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	ordering, ok := channeltypes.Order_value[order]
+	if !ok {
+		return encodeErrToResultBytes(result.ExecuteError, fmt.Errorf("invalid channel order %s", order))
+	}
+
+	envA, envB, unlock, err := loadEnvPair(envIdA, envIdB)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+	defer func() {
+		storeEnv(envIdA, envA)
+		storeEnv(envIdB, envB)
+	}()
+
+	a := endpoint{env: &envA, clientID: path.ClientA, connectionID: path.ConnectionA}
+	b := endpoint{env: &envB, clientID: path.ClientB, connectionID: path.ConnectionB}
+
+	channel, err := openChannel(a, b, portA, portB, version, channeltypes.Order(ordering))
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	bz, err := json.Marshal(channel)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes(bz)
+}
+
+// ibcChannel identifies both ends of a channel opened on an ibcPath
+type ibcChannel struct {
+	PortA    string `json:"port_a"`
+	ChannelA string `json:"channel_a"`
+	PortB    string `json:"port_b"`
+	ChannelB string `json:"channel_b"`
+}
+
+// openChannel initiates a channel on a and relays its handshake until it is open on both ends
+func openChannel(a, b endpoint, portA, portB, version string, order channeltypes.Order) (ibcChannel, error) {
+	events, err := deliverMsgs(a.env, channeltypes.NewMsgChannelOpenInit(
+		portA, version, order, []string{a.connectionID}, portB, a.env.RelayerAddress(),
+	))
+	if err != nil {
+		return ibcChannel{}, err
+	}
+
+	channelA, err := testenv.EventAttribute(events, channeltypes.EventTypeChannelOpenInit, channeltypes.AttributeKeyChannelID)
+	if err != nil {
+		return ibcChannel{}, err
+	}
+
+	report := relayReport{}
+	for {
+		before := report.Handshakes
+		if err := completeChannelHandshakes(a, b, &report); err != nil {
+			return ibcChannel{}, err
+		}
+		if err := completeChannelHandshakes(b, a, &report); err != nil {
+			return ibcChannel{}, err
+		}
+		if report.Handshakes == before {
+			break
+		}
+	}
+
+	channel, found := a.env.App.Keepers.IBCKeeper.ChannelKeeper.GetChannel(a.env.Ctx, portA, channelA)
+	if !found || channel.State != channeltypes.OPEN {
+		return ibcChannel{}, fmt.Errorf("channel %s/%s did not open", portA, channelA)
+	}
+
+	return ibcChannel{
+		PortA:    portA,
+		ChannelA: channelA,
+		PortB:    portB,
+		ChannelB: channel.Counterparty.ChannelId,
+	}, nil
+}
+
+// relay completes channel handshakes and delivers packets, acks and timeouts in both
+// directions until there is nothing left to relay
+func relay(a, b endpoint) (relayReport, error) {
+	report := relayReport{}
+	for {
+		before := report
+
+		steps := []func() error{
+			func() error { return completeChannelHandshakes(a, b, &report) },
+			func() error { return completeChannelHandshakes(b, a, &report) },
+			func() error { return relayPackets(a, b, &report) },
+			func() error { return relayPackets(b, a, &report) },
+			func() error { return relayAcks(a, b, &report) },
+			func() error { return relayAcks(b, a, &report) },
+		}
+		for _, step := range steps {
+			if err := step(); err != nil {
+				return report, err
+			}
+		}
+
+		if report == before {
+			return report, nil
+		}
+	}
+}
+
+// deliverMsgs executes the messages in a new block without going through the ante handler,
+// the changes are only written if every message succeeds
+func deliverMsgs(env *testenv.TestEnv, msgs ...sdk.Msg) ([]abci.Event, error) {
+	res, err := finalizeUnsignedTx(env, msgs, uint64(ibcBlockTime.Seconds()))
+	if err != nil {
+		return nil, err
+	}
+
+	txResult := res.TxResults[0]
+	if txResult.Code != 0 {
+		return nil, errors.New(txResult.Log)
+	}
+
+	return txResult.Events, nil
+}
+
+// createClient creates a light client of the counterparty on the env
+func createClient(env, counterparty *testenv.TestEnv) (string, error) {
+	// Make sure the historical info of the latest counterparty block is stored
+	if err := emptyBlock(counterparty); err != nil {
+		return "", err
+	}
+
+	header, err := counterparty.LightClientHeader(clienttypes.ZeroHeight())
+	if err != nil {
+		return "", err
+	}
+
+	unbondingPeriod, err := counterparty.App.Keepers.StakingKeeper.UnbondingTime(counterparty.Ctx)
+	if err != nil {
+		return "", err
+	}
+
+	clientState := ibctm.NewClientState(
+		counterparty.Ctx.ChainID(),
+		ibctm.DefaultTrustLevel,
+		unbondingPeriod*2/3,
+		unbondingPeriod,
+		ibcMaxClockDrift,
+		header.GetHeight().(clienttypes.Height),
+		commitmenttypes.GetSDKSpecs(),
+		[]string{upgradetypes.StoreKey, upgradetypes.KeyUpgradedIBCState},
+	)
+
+	msg, err := clienttypes.NewMsgCreateClient(clientState, header.ConsensusState(), env.RelayerAddress())
+	if err != nil {
+		return "", err
+	}
+
+	events, err := deliverMsgs(env, msg)
+	if err != nil {
+		return "", err
+	}
+
+	return testenv.EventAttribute(events, clienttypes.EventTypeCreateClient, clienttypes.AttributeKeyClientID)
+}
+
+// updateClientMsg commits a block on the source so everything it did so far can be proven
+// against the header submitted to the destination
+func updateClientMsg(dst, src endpoint) (sdk.Msg, error) {
+	if err := emptyBlock(src.env); err != nil {
+		return nil, err
+	}
+
+	clientState, err := getClientState(dst)
+	if err != nil {
+		return nil, err
+	}
+
+	header, err := src.env.LightClientHeader(clientState.GetLatestHeight().(clienttypes.Height))
+	if err != nil {
+		return nil, err
+	}
+
+	return clienttypes.NewMsgUpdateClient(dst.clientID, header, dst.env.RelayerAddress())
+}
+
+func getClientState(e endpoint) (ibcexported.ClientState, error) {
+	clientState, found := e.env.App.Keepers.IBCKeeper.ClientKeeper.GetClientState(e.env.Ctx, e.clientID)
+	if !found {
+		return nil, fmt.Errorf("client %s not found", e.clientID)
+	}
+
+	return clientState, nil
+}
+
+// queryProof proves the IBC store key against the header of the latest block
+func queryProof(env *testenv.TestEnv, key []byte) ([]byte, clienttypes.Height, error) {
+	res, err := env.App.Query(env.Ctx, &abci.RequestQuery{
+		Path:   fmt.Sprintf("store/%s/key", ibcexported.StoreKey),
+		Data:   key,
+		Height: env.Ctx.BlockHeight() - 1,
+		Prove:  true,
+	})
+	if err != nil {
+		return nil, clienttypes.ZeroHeight(), err
+	}
+	if res.Code != 0 {
+		return nil, clienttypes.ZeroHeight(), errors.New(res.Log)
+	}
+
+	merkleProof, err := commitmenttypes.ConvertProofs(res.ProofOps)
+	if err != nil {
+		return nil, clienttypes.ZeroHeight(), err
+	}
+
+	proof, err := env.App.AppCodec().Marshal(&merkleProof)
+	if err != nil {
+		return nil, clienttypes.ZeroHeight(), err
+	}
+
+	// The app hash of a block commits to the state of the previous one
+	return proof, clienttypes.NewHeight(env.Revision(), uint64(res.Height)+1), nil
+}
+
+type connectionProofs struct {
+	clientState     ibcexported.ClientState
+	connection      []byte
+	client          []byte
+	consensus       []byte
+	proofHeight     clienttypes.Height
+	consensusHeight clienttypes.Height
+}
+
+// queryConnectionProofs proves the connection of the endpoint along with the state of its client
+func queryConnectionProofs(e endpoint) (connectionProofs, error) {
+	clientState, err := getClientState(e)
+	if err != nil {
+		return connectionProofs{}, err
+	}
+	consensusHeight := clientState.GetLatestHeight().(clienttypes.Height)
+
+	connection, proofHeight, err := queryProof(e.env, host.ConnectionKey(e.connectionID))
+	if err != nil {
+		return connectionProofs{}, err
+	}
+	client, _, err := queryProof(e.env, host.FullClientStateKey(e.clientID))
+	if err != nil {
+		return connectionProofs{}, err
+	}
+	consensus, _, err := queryProof(e.env, host.FullConsensusStateKey(e.clientID, consensusHeight))
+	if err != nil {
+		return connectionProofs{}, err
+	}
+
+	return connectionProofs{
+		clientState:     clientState,
+		connection:      connection,
+		client:          client,
+		consensus:       consensus,
+		proofHeight:     proofHeight,
+		consensusHeight: consensusHeight,
+	}, nil
+}
+
+// openConnection runs the connection handshake, initiated by a
+func openConnection(a, b *endpoint) error {
+	prefix := commitmenttypes.NewMerklePrefix([]byte(ibcexported.StoreKey))
+	version := connectiontypes.GetCompatibleVersions()[0]
+
+	// Init
+	events, err := deliverMsgs(a.env, connectiontypes.NewMsgConnectionOpenInit(
+		a.clientID, b.clientID, prefix, version, 0, a.env.RelayerAddress(),
+	))
+	if err != nil {
+		return err
+	}
+	if a.connectionID, err = testenv.EventAttribute(events, connectiontypes.EventTypeConnectionOpenInit, connectiontypes.AttributeKeyConnectionID); err != nil {
+		return err
+	}
+
+	// Try
+	update, err := updateClientMsg(*b, *a)
+	if err != nil {
+		return err
+	}
+	proofs, err := queryConnectionProofs(*a)
+	if err != nil {
+		return err
+	}
+	events, err = deliverMsgs(b.env, update, connectiontypes.NewMsgConnectionOpenTry(
+		b.clientID, a.connectionID, a.clientID, proofs.clientState, prefix,
+		[]*connectiontypes.Version{version}, 0, proofs.connection, proofs.client, proofs.consensus,
+		proofs.proofHeight, proofs.consensusHeight, b.env.RelayerAddress(),
+	))
+	if err != nil {
+		return err
+	}
+	if b.connectionID, err = testenv.EventAttribute(events, connectiontypes.EventTypeConnectionOpenTry, connectiontypes.AttributeKeyConnectionID); err != nil {
+		return err
+	}
+
+	// Ack
+	update, err = updateClientMsg(*a, *b)
+	if err != nil {
+		return err
+	}
+	proofs, err = queryConnectionProofs(*b)
+	if err != nil {
+		return err
+	}
+	if _, err = deliverMsgs(a.env, update, connectiontypes.NewMsgConnectionOpenAck(
+		a.connectionID, b.connectionID, proofs.clientState, proofs.connection, proofs.client,
+		proofs.consensus, proofs.proofHeight, proofs.consensusHeight, version, a.env.RelayerAddress(),
+	)); err != nil {
+		return err
+	}
+
+	// Confirm
+	update, err = updateClientMsg(*b, *a)
+	if err != nil {
+		return err
+	}
+	proof, proofHeight, err := queryProof(a.env, host.ConnectionKey(a.connectionID))
+	if err != nil {
+		return err
+	}
+	_, err = deliverMsgs(b.env, update, connectiontypes.NewMsgConnectionOpenConfirm(
+		b.connectionID, proof, proofHeight, b.env.RelayerAddress(),
+	))
+
+	return err
+}
+
+// onPath checks if the channel belongs to the connection of the endpoint
+func onPath(e endpoint, portID, channelID string) bool {
+	channel, found := e.env.App.Keepers.IBCKeeper.ChannelKeeper.GetChannel(e.env.Ctx, portID, channelID)
+	return found && len(channel.ConnectionHops) > 0 && channel.ConnectionHops[0] == e.connectionID
+}
+
+// findCounterpartyChannel looks for the channel on the endpoint opened for the given counterparty channel
+func findCounterpartyChannel(e endpoint, counterparty channeltypes.IdentifiedChannel) (channeltypes.IdentifiedChannel, bool) {
+	for _, channel := range e.env.App.Keepers.IBCKeeper.ChannelKeeper.GetAllChannels(e.env.Ctx) {
+		if channel.Counterparty.PortId == counterparty.PortId && channel.Counterparty.ChannelId == counterparty.ChannelId {
+			return channel, true
+		}
+	}
+
+	return channeltypes.IdentifiedChannel{}, false
+}
+
+// completeChannelHandshakes moves forward the handshakes of the channels initiated on src
+func completeChannelHandshakes(src, dst endpoint, report *relayReport) error {
+	for _, channel := range src.env.App.Keepers.IBCKeeper.ChannelKeeper.GetAllChannels(src.env.Ctx) {
+		if len(channel.ConnectionHops) == 0 || channel.ConnectionHops[0] != src.connectionID {
+			continue
+		}
+
+		counterparty, found := findCounterpartyChannel(dst, channel)
+
+		var err error
+		switch {
+		case channel.State == channeltypes.INIT && !found:
+			err = channelOpenTry(dst, src, channel)
+		case channel.State == channeltypes.INIT && counterparty.State == channeltypes.TRYOPEN:
+			err = channelOpenAck(src, dst, channel, counterparty)
+		case channel.State == channeltypes.OPEN && counterparty.State == channeltypes.TRYOPEN:
+			err = channelOpenConfirm(dst, src, counterparty, channel)
+		default:
+			continue
+		}
+		if err != nil {
+			return errors.Wrapf(err, "channel handshake of %s/%s", channel.PortId, channel.ChannelId)
+		}
+
+		report.Handshakes++
+	}
+
+	return nil
+}
+
+func channelOpenTry(dst, src endpoint, channel channeltypes.IdentifiedChannel) error {
+	update, err := updateClientMsg(dst, src)
+	if err != nil {
+		return err
+	}
+	proof, proofHeight, err := queryProof(src.env, host.ChannelKey(channel.PortId, channel.ChannelId))
+	if err != nil {
+		return err
+	}
+
+	_, err = deliverMsgs(dst.env, update, channeltypes.NewMsgChannelOpenTry(
+		channel.Counterparty.PortId, channel.Version, channel.Ordering, []string{dst.connectionID},
+		channel.PortId, channel.ChannelId, channel.Version, proof, proofHeight, dst.env.RelayerAddress(),
+	))
+
+	return err
+}
+
+func channelOpenAck(dst, src endpoint, channel, counterparty channeltypes.IdentifiedChannel) error {
+	update, err := updateClientMsg(dst, src)
+	if err != nil {
+		return err
+	}
+	proof, proofHeight, err := queryProof(src.env, host.ChannelKey(counterparty.PortId, counterparty.ChannelId))
+	if err != nil {
+		return err
+	}
+
+	_, err = deliverMsgs(dst.env, update, channeltypes.NewMsgChannelOpenAck(
+		channel.PortId, channel.ChannelId, counterparty.ChannelId, counterparty.Version,
+		proof, proofHeight, dst.env.RelayerAddress(),
+	))
+
+	return err
+}
+
+func channelOpenConfirm(dst, src endpoint, channel, counterparty channeltypes.IdentifiedChannel) error {
+	update, err := updateClientMsg(dst, src)
+	if err != nil {
+		return err
+	}
+	proof, proofHeight, err := queryProof(src.env, host.ChannelKey(counterparty.PortId, counterparty.ChannelId))
+	if err != nil {
+		return err
+	}
+
+	_, err = deliverMsgs(dst.env, update, channeltypes.NewMsgChannelOpenConfirm(
+		channel.PortId, channel.ChannelId, proof, proofHeight, dst.env.RelayerAddress(),
+	))
+
+	return err
+}
+
+// relayPackets delivers the packets sent by src, or times them out if dst can no longer receive them
+func relayPackets(src, dst endpoint, report *relayReport) error {
+	pending := src.env.PendingPackets
+	src.env.PendingPackets = nil
+
+	var remaining []channeltypes.Packet
+	for i, packet := range pending {
+		if !onPath(src, packet.SourcePort, packet.SourceChannel) {
+			remaining = append(remaining, packet)
+			continue
+		}
+
+		// The commitment is deleted once the packet is acknowledged or timed out
+		commitment := src.env.App.Keepers.IBCKeeper.ChannelKeeper.GetPacketCommitment(src.env.Ctx, packet.SourcePort, packet.SourceChannel, packet.Sequence)
+		if len(commitment) == 0 {
+			continue
+		}
+
+		timedOut := timedOutOnNextBlock(dst.env, packet)
+
+		var err error
+		if timedOut {
+			err = timeoutPacket(src, dst, packet)
+		} else {
+			err = recvPacket(dst, src, packet)
+		}
+		if err != nil {
+			// Keep the packets that were not relayed
+			src.env.PendingPackets = append(append(remaining, pending[i:]...), src.env.PendingPackets...)
+			return errors.Wrapf(err, "relay packet %d of %s/%s", packet.Sequence, packet.SourcePort, packet.SourceChannel)
+		}
+
+		if timedOut {
+			report.Timeouts++
+		} else {
+			report.Packets++
+		}
+	}
+
+	// Packets sent while relaying are kept after the ones that were already pending
+	src.env.PendingPackets = append(remaining, src.env.PendingPackets...)
+
+	return nil
+}
+
+// timedOutOnNextBlock checks if the packet would be rejected by the next block of the env
+func timedOutOnNextBlock(env *testenv.TestEnv, packet channeltypes.Packet) bool {
+	height := clienttypes.NewHeight(env.Revision(), uint64(env.Ctx.BlockHeight()+1))
+	timestamp := uint64(env.Ctx.BlockTime().Add(ibcBlockTime).UnixNano())
+
+	timeoutHeight := packet.GetTimeoutHeight()
+	if !timeoutHeight.IsZero() && height.GTE(timeoutHeight) {
+		return true
+	}
+
+	return packet.GetTimeoutTimestamp() != 0 && timestamp >= packet.GetTimeoutTimestamp()
+}
+
+func recvPacket(dst, src endpoint, packet channeltypes.Packet) error {
+	update, err := updateClientMsg(dst, src)
+	if err != nil {
+		return err
+	}
+	proof, proofHeight, err := queryProof(src.env, host.PacketCommitmentKey(packet.SourcePort, packet.SourceChannel, packet.Sequence))
+	if err != nil {
+		return err
+	}
+
+	_, err = deliverMsgs(dst.env, update, channeltypes.NewMsgRecvPacket(packet, proof, proofHeight, dst.env.RelayerAddress()))
+
+	return err
+}
+
+// timeoutPacket proves on src that dst did not receive the packet. Updating the client
+// commits the block of dst in which the packet timed out.
+func timeoutPacket(src, dst endpoint, packet channeltypes.Packet) error {
+	update, err := updateClientMsg(src, dst)
+	if err != nil {
+		return err
+	}
+
+	channel, found := src.env.App.Keepers.IBCKeeper.ChannelKeeper.GetChannel(src.env.Ctx, packet.SourcePort, packet.SourceChannel)
+	if !found {
+		return fmt.Errorf("channel %s/%s not found", packet.SourcePort, packet.SourceChannel)
+	}
+
+	// Ordered channels prove the next sequence to receive instead of the missing receipt
+	key := host.PacketReceiptKey(packet.DestinationPort, packet.DestinationChannel, packet.Sequence)
+	nextSequenceRecv := packet.Sequence
+	if channel.Ordering == channeltypes.ORDERED {
+		key = host.NextSequenceRecvKey(packet.DestinationPort, packet.DestinationChannel)
+		nextSequenceRecv, found = dst.env.App.Keepers.IBCKeeper.ChannelKeeper.GetNextSequenceRecv(dst.env.Ctx, packet.DestinationPort, packet.DestinationChannel)
+		if !found {
+			return fmt.Errorf("next sequence receive of %s/%s not found", packet.DestinationPort, packet.DestinationChannel)
+		}
+	}
+
+	proof, proofHeight, err := queryProof(dst.env, key)
+	if err != nil {
+		return err
+	}
+
+	_, err = deliverMsgs(src.env, update, channeltypes.NewMsgTimeout(packet, nextSequenceRecv, proof, proofHeight, src.env.RelayerAddress()))
+
+	return err
+}
+
+// relayAcks delivers to dst the acknowledgements src wrote for the packets it received
+func relayAcks(src, dst endpoint, report *relayReport) error {
+	pending := src.env.PendingAcks
+	src.env.PendingAcks = nil
+
+	var remaining []testenv.PacketAck
+	for i, ack := range pending {
+		packet := ack.Packet
+		if !onPath(src, packet.DestinationPort, packet.DestinationChannel) {
+			remaining = append(remaining, ack)
+			continue
+		}
+
+		commitment := dst.env.App.Keepers.IBCKeeper.ChannelKeeper.GetPacketCommitment(dst.env.Ctx, packet.SourcePort, packet.SourceChannel, packet.Sequence)
+		if len(commitment) == 0 {
+			continue
+		}
+
+		if err := acknowledgePacket(dst, src, ack); err != nil {
+			src.env.PendingAcks = append(append(remaining, pending[i:]...), src.env.PendingAcks...)
+			return errors.Wrapf(err, "relay ack %d of %s/%s", packet.Sequence, packet.SourcePort, packet.SourceChannel)
+		}
+		report.Acks++
+	}
+
+	src.env.PendingAcks = append(remaining, src.env.PendingAcks...)
+
+	return nil
+}
+
+func acknowledgePacket(dst, src endpoint, ack testenv.PacketAck) error {
+	packet := ack.Packet
+	update, err := updateClientMsg(dst, src)
+	if err != nil {
+		return err
+	}
+	proof, proofHeight, err := queryProof(src.env, host.PacketAcknowledgementKey(packet.DestinationPort, packet.DestinationChannel, packet.Sequence))
+	if err != nil {
+		return err
+	}
+
+	_, err = deliverMsgs(dst.env, update, channeltypes.NewMsgAcknowledgement(packet, ack.Ack, proof, proofHeight, dst.env.RelayerAddress()))
+
+	return err
+}
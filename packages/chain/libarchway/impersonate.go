@@ -0,0 +1,131 @@
+package main
+
+import "C"
+
+import (
+	// std
+	"bytes"
+	"encoding/base64"
+	"fmt"
+
+	// helpers
+	proto "github.com/cosmos/gogoproto/proto"
+	"github.com/pkg/errors"
+
+	// tendermint
+	abci "github.com/cometbft/cometbft/abci/types"
+
+	// cosmos sdk
+	codectypes "github.com/cosmos/cosmos-sdk/codec/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+	txtypes "github.com/cosmos/cosmos-sdk/types/tx"
+
+	// cosmwasm-testing
+	"github.com/FloppyDisck/archway-test-tube/result"
+	"github.com/FloppyDisck/archway-test-tube/testenv"
+)
+
+// ExecuteAs executes the messages of a tx body in a new block on behalf of an address without its key.
+// The tx skips the ante handler, so no signature, sequence or fee is checked,
+// but every message must be signed by the impersonated address.
+//
+//export ExecuteAs
+func ExecuteAs(envId uint64, bech32Address, base64TxBodyBytes string) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+
+	signer, err := sdk.AccAddressFromBech32(bech32Address)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	bodyBytes, err := base64.StdEncoding.DecodeString(base64TxBodyBytes)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	var body txtypes.TxBody
+	if err := env.App.AppCodec().Unmarshal(bodyBytes, &body); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	msgs, err := txtypes.GetMsgs(body.Messages, "tx body")
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	for _, msg := range msgs {
+		signers, _, err := env.App.AppCodec().GetMsgV1Signers(msg)
+		if err != nil {
+			return encodeErrToResultBytes(result.ExecuteError, err)
+		}
+		for _, msgSigner := range signers {
+			if !bytes.Equal(msgSigner, signer) {
+				return encodeErrToResultBytes(result.ExecuteError, fmt.Errorf(
+					"%s must be signed by %s", sdk.MsgTypeURL(msg), sdk.AccAddress(msgSigner),
+				))
+			}
+		}
+	}
+
+	res, err := finalizeUnsignedTx(&env, msgs, 5)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	storeEnv(envId, env)
+
+	if txResult := res.TxResults[0]; txResult.Code != 0 {
+		return encodeErrToResultBytes(result.ExecuteError, errors.New(txResult.Log))
+	}
+
+	bz, err := proto.Marshal(res)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes(bz)
+}
+
+// finalizeUnsignedTx runs the messages in a new block as an unsigned tx skipping the ante handler,
+// then commits the block even if the tx fails
+func finalizeUnsignedTx(env *testenv.TestEnv, msgs []sdk.Msg, seconds uint64) (*abci.ResponseFinalizeBlock, error) {
+	anys := make([]*codectypes.Any, 0, len(msgs))
+	for _, msg := range msgs {
+		msgAny, err := codectypes.NewAnyWithValue(msg)
+		if err != nil {
+			return nil, err
+		}
+		anys = append(anys, msgAny)
+	}
+
+	bodyBytes, err := proto.Marshal(&txtypes.TxBody{Messages: anys})
+	if err != nil {
+		return nil, err
+	}
+	authInfoBytes, err := proto.Marshal(&txtypes.AuthInfo{Fee: &txtypes.Fee{}})
+	if err != nil {
+		return nil, err
+	}
+	txBytes, err := proto.Marshal(&txtypes.TxRaw{BodyBytes: bodyBytes, AuthInfoBytes: authInfoBytes})
+	if err != nil {
+		return nil, err
+	}
+
+	env.AnteBypass.Add(txBytes)
+	defer env.AnteBypass.Remove(txBytes)
+
+	res, err := finalizeBlock(env, [][]byte{txBytes}, seconds)
+	if err != nil {
+		return nil, err
+	}
+
+	if _, err := commitWithCustomIncBlockTime(env); err != nil {
+		return nil, err
+	}
+
+	return res, nil
+}
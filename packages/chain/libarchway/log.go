@@ -0,0 +1,188 @@
+package main
+
+import "C"
+
+import (
+	// std
+	"fmt"
+	"io"
+	"os"
+	"sync"
+	"time"
+
+	// helpers
+	"github.com/rs/zerolog"
+
+	// cosmos sdk
+	"cosmossdk.io/log"
+
+	// cosmwasm-testing
+	"github.com/FloppyDisck/archway-test-tube/result"
+	"github.com/FloppyDisck/archway-test-tube/testenv"
+)
+
+// logSink is where the apps of new envs write their logs, they are discarded by default
+var logSink = struct {
+	sync.Mutex
+	writer io.Writer
+	level  zerolog.Level
+	// Set when the writer is a file
+	file *logFile
+}{writer: io.Discard, level: zerolog.InfoLevel}
+
+// logFile is shared by the sink and the envs created while it was set, it is closed once none of
+// them uses it anymore. The references are counted under the lock of the sink.
+type logFile struct {
+	*os.File
+	refs int
+}
+
+func (f *logFile) release() {
+	f.refs--
+	if f.refs == 0 {
+		f.Close()
+	}
+}
+
+// SetLogSink sets where the envs created afterwards write their logs. The sink is either
+// discard, stdout, stderr or the path of a file the logs are appended to.
+//
+//export SetLogSink
+func SetLogSink(sink, level string) *C.char {
+	lvl, err := zerolog.ParseLevel(level)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	var writer io.Writer
+	var file *logFile
+	switch sink {
+	case "discard":
+		writer = io.Discard
+	case "stdout":
+		writer = os.Stdout
+	case "stderr":
+		writer = os.Stderr
+	case "":
+		return encodeErrToResultBytes(result.ExecuteError, fmt.Errorf("log sink must not be empty"))
+	default:
+		opened, err := os.OpenFile(sink, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0o644)
+		if err != nil {
+			return encodeErrToResultBytes(result.ExecuteError, err)
+		}
+		file = &logFile{File: opened, refs: 1}
+		writer = file
+	}
+
+	logSink.Lock()
+	defer logSink.Unlock()
+
+	// The replaced file stays open for the envs still logging to it
+	if logSink.file != nil {
+		logSink.file.release()
+	}
+	logSink.writer = writer
+	logSink.level = lvl
+	logSink.file = file
+
+	return encodeBytesResultBytes([]byte{})
+}
+
+// SetLogCapture keeps the log entries of the env from the given level on until they are taken,
+// the disabled level stops the capture
+//
+//export SetLogCapture
+func SetLogCapture(envId uint64, level string) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+
+	lvl, err := zerolog.ParseLevel(level)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	env.Logs.SetLevel(lvl)
+
+	return encodeBytesResultBytes([]byte{})
+}
+
+// TakeLogs returns the json entries captured since the last call
+//
+//export TakeLogs
+func TakeLogs(envId uint64) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
+
+	return encodeJsonResultBytes(result.QueryError, env.Logs.Take())
+}
+
+// SetContractDebugCapture attaches the messages printed by the contracts with deps.api.debug
+// to the tx results as an event, see testenv.WithContractDebug
+//
+//export SetContractDebugCapture
+func SetContractDebugCapture(envId uint64, enabled bool) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+
+	env.CaptureContractDebug = enabled
+	storeEnv(envId, env)
+
+	return encodeBytesResultBytes([]byte{})
+}
+
+// newLogger returns a logger writing to the current sink and to the capture of the env, along with
+// the function releasing the sink once the env is removed
+func newLogger(capture *testenv.LogCapture) (log.Logger, func()) {
+	logSink.Lock()
+	defer logSink.Unlock()
+
+	writer := &envLogWriter{sinkLevel: logSink.level, capture: capture}
+	if logSink.writer != io.Discard {
+		writer.sink = zerolog.ConsoleWriter{Out: logSink.writer, NoColor: true, TimeFormat: time.Kitchen}
+	}
+
+	release := func() {}
+	if file := logSink.file; file != nil {
+		file.refs++
+		release = func() {
+			logSink.Lock()
+			defer logSink.Unlock()
+			file.release()
+		}
+	}
+
+	// The writer filters the levels, as the capture level can change after the logger is created
+	return log.NewLogger(writer, log.OutputJSONOption(), log.LevelOption(zerolog.TraceLevel)), release
+}
+
+// envLogWriter formats the json entries of the logger for the sink and hands them to the capture
+type envLogWriter struct {
+	sink      io.Writer
+	sinkLevel zerolog.Level
+	capture   *testenv.LogCapture
+}
+
+var _ zerolog.LevelWriter = &envLogWriter{}
+
+func (w *envLogWriter) Write(p []byte) (int, error) {
+	return w.WriteLevel(zerolog.NoLevel, p)
+}
+
+func (w *envLogWriter) WriteLevel(level zerolog.Level, p []byte) (int, error) {
+	if w.sink != nil && level >= w.sinkLevel {
+		if _, err := w.sink.Write(p); err != nil {
+			return 0, err
+		}
+	}
+	w.capture.Capture(level, p)
+
+	return len(p), nil
+}
@@ -10,6 +10,7 @@ import (
 	banktestutil "github.com/cosmos/cosmos-sdk/x/bank/testutil"
 	"os"
 	"sync"
+	"sync/atomic"
 	"time"
 
 	// helpers
@@ -22,6 +23,7 @@ import (
 	// cosmos sdk
 	coreheader "cosmossdk.io/core/header"
 	codectypes "github.com/cosmos/cosmos-sdk/codec/types"
+	"github.com/cosmos/cosmos-sdk/crypto/hd"
 	"github.com/cosmos/cosmos-sdk/crypto/keys/secp256k1"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	banktypes "github.com/cosmos/cosmos-sdk/x/bank/types"
@@ -34,65 +36,100 @@ import (
 )
 
 var (
-	envCounter  uint64 = 0
-	envRegister        = sync.Map{}
-	mu          sync.Mutex
+	envCounter uint64 = 0
+	// Maps the env ids to their *envEntry
+	envRegister = sync.Map{}
+	// Process wide settings shared by every env
+	setupGlobals sync.Once
 )
 
 //export InitTestEnv
-func InitTestEnv() uint64 {
-	// Temp fix for concurrency issue
-	mu.Lock()
-	defer mu.Unlock()
+func InitTestEnv() *C.char {
+	return encodeEnvId(initTestEnv("archway-1"))
+}
 
-	// temp: suppress noise from stdout
-	os.Stdout = nil
+//export InitTestEnvWithChainId
+func InitTestEnvWithChainId(chainId string) *C.char {
+	return encodeEnvId(initTestEnv(chainId))
+}
 
-	envCounter += 1
-	id := envCounter
+// encodeEnvId returns the id of the new env as json
+func encodeEnvId(id uint64, err error) *C.char {
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	return encodeJsonResultBytes(result.ExecuteError, id)
+}
+
+func initTestEnv(chainID string) (uint64, error) {
+	setupGlobals.Do(func() {
+		sdk.DefaultBondDenom = "aarch"
+		// Allow testing unoptimized contract
+		wasmtypes.MaxWasmSize = 1024 * 1024 * 1024 * 1024 * 1024
+	})
 
 	nodeHome, err := os.MkdirTemp("", ".archway-test-tube-temp-")
 	if err != nil {
-		panic(err)
+		return 0, err
 	}
 
 	env := new(testenv.TestEnv)
-	env.App = testenv.NewArchwayApp(nodeHome)
+	env.Logs = testenv.NewLogCapture()
+	logger, releaseLogs := newLogger(env.Logs)
+	env.App = testenv.NewArchwayApp(nodeHome, chainID, logger)
 	env.NodeHome = nodeHome
 	env.ParamTypesRegistry = *testenv.NewParamTypeRegistry()
-
-	ctx, valPriv := testenv.InitChain(env.App)
+	env.GasTracer = &testenv.GasTracer{}
+	env.StoreTracer = &testenv.StoreTracer{}
+	env.AnteBypass = &testenv.AnteBypass{}
+	env.BypassAnte()
+	env.TraceGas()
+	env.TraceStorage()
+
+	ctx, valPriv, err := testenv.InitChain(env.App, chainID)
+	if err != nil {
+		releaseLogs()
+		return 0, errors.Wrap(err, "init chain")
+	}
 
 	env.Ctx = ctx
 	env.ValPrivs = []*secp256k1.PrivKey{&valPriv}
 
 	env.SetupParamTypes()
 
-	// Allow testing unoptimized contract
-	wasmtypes.MaxWasmSize = 1024 * 1024 * 1024 * 1024 * 1024
-
-	env.FundValidators()
+	if err := env.FundValidators(); err != nil {
+		releaseLogs()
+		return 0, err
+	}
 	//env.BeginNewBlock(false, 5)
 	//
 	//env.EndBlock()
 	err = emptyBlock(env)
 	if err != nil {
-		panic(err)
+		releaseLogs()
+		return 0, err
 	}
 
-	envRegister.Store(id, *env)
+	id := atomic.AddUint64(&envCounter, 1)
+	envRegister.Store(id, &envEntry{env: *env, releaseLogs: releaseLogs})
 
-	return id
+	return id, nil
 }
 
 //export CleanUp
-func CleanUp(envId uint64) {
-	env := loadEnv(envId)
-	err := os.RemoveAll(env.NodeHome)
+func CleanUp(envId uint64) *C.char {
+	env, unlock, err := loadEnv(envId)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+	removeEnv(envId)
+
+	if err := os.RemoveAll(env.NodeHome); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
-	envRegister.Delete(envId)
+
+	return encodeBytesResultBytes([]byte{})
 }
 
 func VerifyAccountExists(env *testenv.TestEnv, accAddr sdk.AccAddress) bool {
@@ -103,19 +140,117 @@ func VerifyAccountExists(env *testenv.TestEnv, accAddr sdk.AccAddress) bool {
 
 //export InitAccount
 func InitAccount(envId uint64, coinsJson string) *C.char {
-	env := loadEnv(envId)
+	return initAccountWithKey(envId, secp256k1.GenPrivKey(), coinsJson)
+}
 
-	var coins sdk.Coins
+//export InitAccountFromSeed
+func InitAccountFromSeed(envId uint64, seed, coinsJson string) *C.char {
+	priv := secp256k1.GenPrivKeyFromSecret([]byte(seed))
+	return initAccountWithKey(envId, priv, coinsJson)
+}
 
-	if err := json.Unmarshal([]byte(coinsJson), &coins); err != nil {
-		panic(err)
+//export InitAccountFromMnemonic
+func InitAccountFromMnemonic(envId uint64, mnemonic, hdPath, coinsJson string) *C.char {
+	derived, err := hd.Secp256k1.Derive()(mnemonic, "", hdPath)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 
-	priv := secp256k1.GenPrivKey()
+	return initAccountWithKey(envId, &secp256k1.PrivKey{Key: derived}, coinsJson)
+}
+
+// initAccountWithKey funds the account of the given key, returning the base64 encoded key
+func initAccountWithKey(envId uint64, priv *secp256k1.PrivKey, coinsJson string) *C.char {
 	accAddr := sdk.AccAddress(priv.PubKey().Address())
 
+	err := fundEnv(envId, coinsJson, func(env *testenv.TestEnv, coins sdk.Coins) error {
+		registerDenoms(env, coins)
+		return banktestutil.FundAccount(env.Ctx, env.App.Keepers.BankKeeper, accAddr, coins)
+	})
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes([]byte(base64.StdEncoding.EncodeToString(priv.Bytes())))
+}
+
+//export MintTo
+func MintTo(envId uint64, bech32Address, coinsJson string) *C.char {
+	accAddr, err := sdk.AccAddressFromBech32(bech32Address)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	err = fundEnv(envId, coinsJson, func(env *testenv.TestEnv, coins sdk.Coins) error {
+		registerDenoms(env, coins)
+		return banktestutil.FundAccount(env.Ctx, env.App.Keepers.BankKeeper, accAddr, coins)
+	})
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes([]byte{})
+}
+
+//export FundAccount
+func FundAccount(envId uint64, bech32Address, coinsJson string) *C.char {
+	accAddr, err := sdk.AccAddressFromBech32(bech32Address)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	err = fundEnv(envId, coinsJson, func(env *testenv.TestEnv, coins sdk.Coins) error {
+		return banktestutil.FundAccount(env.Ctx, env.App.Keepers.BankKeeper, accAddr, coins)
+	})
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes([]byte{})
+}
+
+//export FundModuleAccount
+func FundModuleAccount(envId uint64, moduleName, coinsJson string) *C.char {
+	err := fundEnv(envId, coinsJson, func(env *testenv.TestEnv, coins sdk.Coins) error {
+		return banktestutil.FundModuleAccount(env.Ctx, env.App.Keepers.BankKeeper, moduleName, coins)
+	})
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes([]byte{})
+}
+
+// fundEnv mints the coins through the given funding function and commits them in an empty block,
+// so they are visible to simulations
+func fundEnv(envId uint64, coinsJson string, fund func(env *testenv.TestEnv, coins sdk.Coins) error) error {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return err
+	}
+	defer unlock()
+
+	var coins sdk.Coins
+	if err := json.Unmarshal([]byte(coinsJson), &coins); err != nil {
+		return err
+	}
+
+	if err := fund(&env, coins); err != nil {
+		return errors.Wrapf(err, "Failed to fund account")
+	}
+
+	if err := emptyBlock(&env); err != nil {
+		return err
+	}
+
+	storeEnv(envId, env)
+
+	return nil
+}
+
+// registerDenoms creates the metadata of the denoms that do not exist yet
+func registerDenoms(env *testenv.TestEnv, coins sdk.Coins) {
 	for _, coin := range coins {
-		// create denom if not exist
 		_, hasDenomMetaData := env.App.Keepers.BankKeeper.GetDenomMetaData(env.Ctx, coin.Denom)
 		if !hasDenomMetaData {
 			denomMetaData := banktypes.Metadata{
@@ -129,46 +264,77 @@ func InitAccount(envId uint64, coinsJson string) *C.char {
 			env.App.Keepers.BankKeeper.SetDenomMetaData(env.Ctx, denomMetaData)
 		}
 	}
+}
 
-	err := banktestutil.FundAccount(env.Ctx, env.App.Keepers.BankKeeper, accAddr, coins)
+// SetGasTracing attaches the gas breakdown of every tx to its result as an event
+//
+//export SetGasTracing
+func SetGasTracing(envId uint64, enabled bool) *C.char {
+	env, unlock, err := loadEnv(envId)
 	if err != nil {
-		panic(errors.Wrapf(err, "Failed to fund account"))
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
+	defer unlock()
 
-	err = emptyBlock(&env)
+	env.GasTracer.Enabled = enabled
+	storeEnv(envId, env)
+
+	return encodeBytesResultBytes([]byte{})
+}
+
+// SetStorageTracing attaches the KV store operations of every tx to its result as an event
+//
+//export SetStorageTracing
+func SetStorageTracing(envId uint64, enabled bool) *C.char {
+	env, unlock, err := loadEnv(envId)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
+	defer unlock()
 
-	envRegister.Store(envId, env)
+	env.StoreTracer.Enabled = enabled
+	storeEnv(envId, env)
 
-	return C.CString(base64.StdEncoding.EncodeToString(priv.Bytes()))
+	return encodeBytesResultBytes([]byte{})
 }
 
+// IncreaseTime commits an empty block the given seconds after the current one, returning its time in nanoseconds
+//
 //export IncreaseTime
-func IncreaseTime(envId uint64, seconds uint64) int64 {
-	env := loadEnv(envId)
-	_, err := finalizeBlock(&env, [][]byte{}, seconds)
+func IncreaseTime(envId uint64, seconds uint64) *C.char {
+	env, unlock, err := loadEnv(envId)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+
+	_, err = finalizeBlock(&env, [][]byte{}, seconds)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 	_, err = commitWithCustomIncBlockTime(&env)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
-	envRegister.Store(envId, env)
+	storeEnv(envId, env)
 
-	return env.Ctx.BlockTime().UnixNano()
+	return encodeJsonResultBytes(result.ExecuteError, env.Ctx.BlockTime().UnixNano())
 }
 
 //export SkipBlock
-func SkipBlock(envId uint64) {
-	env := loadEnv(envId)
-	err := emptyBlock(&env)
+func SkipBlock(envId uint64) *C.char {
+	env, unlock, err := loadEnv(envId)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
-	envRegister.Store(envId, env)
+	defer unlock()
+
+	if err := emptyBlock(&env); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	storeEnv(envId, env)
+
+	return encodeBytesResultBytes([]byte{})
 }
 
 func emptyBlock(env *testenv.TestEnv) error {
@@ -185,7 +351,11 @@ func emptyBlock(env *testenv.TestEnv) error {
 
 //export FinalizeBlock
 func FinalizeBlock(envId uint64, tx string) *C.char {
-	env := loadEnv(envId)
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
 
 	txBytes, err := base64.StdEncoding.DecodeString(tx)
 	if err != nil {
@@ -198,7 +368,42 @@ func FinalizeBlock(envId uint64, tx string) *C.char {
 		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 
-	envRegister.Store(envId, env)
+	storeEnv(envId, env)
+
+	bz, err := proto.Marshal(res)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes(bz)
+}
+
+// FinalizeAndCommit runs the tx in a block and commits it under one lock of the env, so no other
+// call sees or changes the state between the two
+//
+//export FinalizeAndCommit
+func FinalizeAndCommit(envId uint64, tx string) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+
+	txBytes, err := base64.StdEncoding.DecodeString(tx)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	res, err := finalizeBlock(&env, [][]byte{txBytes}, 5)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	if _, err := commitWithCustomIncBlockTime(&env); err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	storeEnv(envId, env)
 
 	bz, err := proto.Marshal(res)
 	if err != nil {
@@ -222,25 +427,66 @@ func finalizeBlock(env *testenv.TestEnv, txs [][]byte, seconds uint64) (*abci.Re
 		Time:   header.Time,
 	})
 
+	// The validators hash is recorded in the historical info that IBC counterparties verify
+	valSet, err := env.ValidatorSet()
+	if err != nil {
+		return nil, err
+	}
+
 	// Finalize the block
-	res, err := env.App.FinalizeBlock(&abci.RequestFinalizeBlock{
-		Txs:    txs,
-		Height: env.Ctx.BlockHeight(),
-		Time:   env.Ctx.BlockTime(),
+	env.GasTracer.Reset()
+	env.StoreTracer.Reset()
+	var res *abci.ResponseFinalizeBlock
+	debugMessages, err := env.WithContractDebug(func() (err error) {
+		res, err = env.App.FinalizeBlock(&abci.RequestFinalizeBlock{
+			Txs:                txs,
+			Height:             env.Ctx.BlockHeight(),
+			Time:               env.Ctx.BlockTime(),
+			NextValidatorsHash: valSet.Hash(),
+		})
+		return err
 	})
+	if err != nil {
+		return nil, err
+	}
+
+	if env.GasTracer.Enabled {
+		if err := env.GasTracer.AttachBreakdowns(txs, res.TxResults); err != nil {
+			return nil, err
+		}
+	}
+	if env.StoreTracer.Enabled {
+		if err := env.StoreTracer.AttachAccesses(txs, res.TxResults); err != nil {
+			return nil, err
+		}
+	}
+	if env.CaptureContractDebug {
+		if err := testenv.AttachDebugMessages(res.TxResults, debugMessages); err != nil {
+			return nil, err
+		}
+	}
+
+	env.TrackIbcEvents(res.Events)
+	for _, txResult := range res.TxResults {
+		env.TrackIbcEvents(txResult.Events)
+	}
 
-	return res, err
+	return res, nil
 }
 
 //export Commit
 func Commit(envId uint64) *C.char {
-	env := loadEnv(envId)
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
 	res, err := commitWithCustomIncBlockTime(&env)
 	if err != nil {
 		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 
-	envRegister.Store(envId, env)
+	storeEnv(envId, env)
 
 	bz, err := proto.Marshal(res)
 	if err != nil {
@@ -261,14 +507,15 @@ func commitWithCustomIncBlockTime(env *testenv.TestEnv) (*abci.ResponseCommit, e
 
 //export WasmSudo
 func WasmSudo(envId uint64, bech32Address, msgJson string) *C.char {
-	env := loadEnv(envId)
-	// Temp fix for concurrency issue
-	mu.Lock()
-	defer mu.Unlock()
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
 
 	accAddr, err := sdk.AccAddressFromBech32(bech32Address)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 
 	msgBytes := []byte(msgJson)
@@ -278,17 +525,22 @@ func WasmSudo(envId uint64, bech32Address, msgJson string) *C.char {
 		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 
-	envRegister.Store(envId, env)
+	storeEnv(envId, env)
 
 	return encodeBytesResultBytes(res)
 }
 
 //export Query
 func Query(envId uint64, path, base64QueryMsgBytes string) *C.char {
-	env := loadEnv(envId)
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
+
 	queryMsgBytes, err := base64.StdEncoding.DecodeString(base64QueryMsgBytes)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.QueryError, err)
 	}
 
 	req := abci.RequestQuery{}
@@ -308,61 +560,83 @@ func Query(envId uint64, path, base64QueryMsgBytes string) *C.char {
 	return encodeBytesResultBytes(res.Value)
 }
 
+// GetBlockTime returns the block time in nanoseconds
+//
 //export GetBlockTime
-func GetBlockTime(envId uint64) int64 {
-	env := loadEnv(envId)
-	return env.Ctx.BlockTime().UnixNano()
+func GetBlockTime(envId uint64) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
+
+	return encodeJsonResultBytes(result.QueryError, env.Ctx.BlockTime().UnixNano())
 }
 
 //export GetBlockHeight
-func GetBlockHeight(envId uint64) int64 {
-	env := loadEnv(envId)
-	return env.Ctx.BlockHeight()
+func GetBlockHeight(envId uint64) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
+
+	return encodeJsonResultBytes(result.QueryError, env.Ctx.BlockHeight())
 }
 
 //export AccountSequence
-func AccountSequence(envId uint64, bech32Address string) uint64 {
-	env := loadEnv(envId)
+func AccountSequence(envId uint64, bech32Address string) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
 
 	addr, err := sdk.AccAddressFromBech32(bech32Address)
-
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.QueryError, err)
 	}
 
 	seq, err := env.App.Keepers.AccountKeeper.GetSequence(env.Ctx, addr)
-
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.QueryError, err)
 	}
 
-	return seq
+	return encodeJsonResultBytes(result.QueryError, seq)
 }
 
 //export AccountNumber
-func AccountNumber(envId uint64, bech32Address string) uint64 {
-	env := loadEnv(envId)
+func AccountNumber(envId uint64, bech32Address string) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
 
 	addr, err := sdk.AccAddressFromBech32(bech32Address)
-
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.QueryError, err)
 	}
 
 	acc := env.App.Keepers.AccountKeeper.GetAccount(env.Ctx, addr)
-	return acc.GetAccountNumber()
+	if acc == nil {
+		return encodeErrToResultBytes(result.QueryError, fmt.Errorf("account %s not found", bech32Address))
+	}
+
+	return encodeJsonResultBytes(result.QueryError, acc.GetAccountNumber())
 }
 
 //export Simulate
 func Simulate(envId uint64, base64TxBytes string) *C.char { // => base64GasInfo
-	env := loadEnv(envId)
-	// Temp fix for concurrency issue
-	mu.Lock()
-	defer mu.Unlock()
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
 
 	txBytes, err := base64.StdEncoding.DecodeString(base64TxBytes)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 
 	gasInfo, _, err := env.App.BaseApp.Simulate(txBytes)
@@ -372,7 +646,70 @@ func Simulate(envId uint64, base64TxBytes string) *C.char { // => base64GasInfo
 
 	bz, err := proto.Marshal(&gasInfo)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	return encodeBytesResultBytes(bz)
+}
+
+// SimulateTx simulates the tx like Simulate does, also returning its events and msg responses
+// in a tx result
+//
+//export SimulateTx
+func SimulateTx(envId uint64, base64TxBytes string) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+
+	txBytes, err := base64.StdEncoding.DecodeString(base64TxBytes)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	env.GasTracer.Reset()
+	env.StoreTracer.Reset()
+	var gasInfo sdk.GasInfo
+	var res *sdk.Result
+	debugMessages, err := env.WithContractDebug(func() (err error) {
+		gasInfo, res, err = env.App.BaseApp.Simulate(txBytes)
+		return err
+	})
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	data, err := proto.Marshal(&sdk.TxMsgData{MsgResponses: res.MsgResponses})
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+
+	txResults := []*abci.ExecTxResult{{
+		Data:      data,
+		GasWanted: int64(gasInfo.GasWanted),
+		GasUsed:   int64(gasInfo.GasUsed),
+		Events:    res.Events,
+	}}
+	if env.GasTracer.Enabled {
+		if err := env.GasTracer.AttachBreakdowns([][]byte{txBytes}, txResults); err != nil {
+			return encodeErrToResultBytes(result.ExecuteError, err)
+		}
+	}
+	if env.StoreTracer.Enabled {
+		if err := env.StoreTracer.AttachAccesses([][]byte{txBytes}, txResults); err != nil {
+			return encodeErrToResultBytes(result.ExecuteError, err)
+		}
+	}
+	if env.CaptureContractDebug {
+		if err := testenv.AttachDebugMessages(txResults, debugMessages); err != nil {
+			return encodeErrToResultBytes(result.ExecuteError, err)
+		}
+	}
+
+	bz, err := proto.Marshal(&abci.ResponseFinalizeBlock{TxResults: txResults})
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 
 	return encodeBytesResultBytes(bz)
@@ -380,15 +717,15 @@ func Simulate(envId uint64, base64TxBytes string) *C.char { // => base64GasInfo
 
 //export SetParamSet
 func SetParamSet(envId uint64, subspaceName, base64ParamSetBytes string) *C.char {
-	env := loadEnv(envId)
-
-	// Temp fix for concurrency issue
-	mu.Lock()
-	defer mu.Unlock()
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
 
 	paramSetBytes, err := base64.StdEncoding.DecodeString(base64ParamSetBytes)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.ExecuteError, err)
 	}
 
 	subspace, ok := env.App.Keepers.ParamsKeeper.GetSubspace(subspaceName)
@@ -420,7 +757,11 @@ func SetParamSet(envId uint64, subspaceName, base64ParamSetBytes string) *C.char
 
 //export GetParamSet
 func GetParamSet(envId uint64, subspaceName, typeUrl string) *C.char {
-	env := loadEnv(envId)
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
 
 	subspace, ok := env.App.Keepers.ParamsKeeper.GetSubspace(subspaceName)
 	if !ok {
@@ -440,7 +781,7 @@ func GetParamSet(envId uint64, subspaceName, typeUrl string) *C.char {
 	bz, err := proto.Marshal(pset)
 
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(result.QueryError, err)
 	}
 
 	return encodeBytesResultBytes(bz)
@@ -448,28 +789,112 @@ func GetParamSet(envId uint64, subspaceName, typeUrl string) *C.char {
 
 //export GetValidatorAddress
 func GetValidatorAddress(envId uint64, n int32) *C.char {
-	env := loadEnv(envId)
-	return C.CString(env.GetValidatorAddresses()[n])
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
+
+	addresses := env.GetValidatorAddresses()
+	if n < 0 || int(n) >= len(addresses) {
+		return encodeErrToResultBytes(result.QueryError, fmt.Errorf("validator %d not found", n))
+	}
+
+	return encodeBytesResultBytes([]byte(addresses[n]))
 }
 
 //export GetValidatorPrivateKey
 func GetValidatorPrivateKey(envId uint64, n int32) *C.char {
-	env := loadEnv(envId)
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
+
+	if n < 0 || int(n) >= len(env.ValPrivs) {
+		return encodeErrToResultBytes(result.QueryError, fmt.Errorf("validator %d not found", n))
+	}
 
 	priv := env.ValPrivs[n].Key
 	base64Priv := base64.StdEncoding.EncodeToString(priv)
-	return C.CString(base64Priv)
+	return encodeBytesResultBytes([]byte(base64Priv))
 }
 
 // ========= utils =========
 
-func loadEnv(envId uint64) testenv.TestEnv {
+// envEntry guards an env. Every export holds the lock of the envs it uses for the whole call,
+// so the calls on an env run one at a time while the other envs keep running in parallel.
+type envEntry struct {
+	sync.Mutex
+	env testenv.TestEnv
+	// Set by CleanUp for the calls waiting on the lock
+	removed bool
+	// Releases the log file the app writes to
+	releaseLogs func()
+}
+
+// loadEnv locks the env and returns a copy of it, along with the function releasing the lock
+func loadEnv(envId uint64) (testenv.TestEnv, func(), error) {
 	item, ok := envRegister.Load(envId)
-	env := testenv.TestEnv(item.(testenv.TestEnv))
 	if !ok {
-		panic(fmt.Sprintf("env not found: %d", envId))
+		return testenv.TestEnv{}, nil, fmt.Errorf("env not found: %d", envId)
+	}
+
+	entry := item.(*envEntry)
+	entry.Lock()
+	if entry.removed {
+		entry.Unlock()
+		return testenv.TestEnv{}, nil, fmt.Errorf("env not found: %d", envId)
+	}
+
+	return entry.env, entry.Unlock, nil
+}
+
+// loadEnvPair locks both envs in the order of their ids, so calls on the same pair can't deadlock
+func loadEnvPair(envIdA, envIdB uint64) (testenv.TestEnv, testenv.TestEnv, func(), error) {
+	if envIdA == envIdB {
+		return testenv.TestEnv{}, testenv.TestEnv{}, nil, fmt.Errorf("env %d is used twice", envIdA)
+	}
+
+	firstId, secondId := envIdA, envIdB
+	if firstId > secondId {
+		firstId, secondId = secondId, firstId
+	}
+
+	first, unlockFirst, err := loadEnv(firstId)
+	if err != nil {
+		return testenv.TestEnv{}, testenv.TestEnv{}, nil, err
+	}
+	second, unlockSecond, err := loadEnv(secondId)
+	if err != nil {
+		unlockFirst()
+		return testenv.TestEnv{}, testenv.TestEnv{}, nil, err
+	}
+
+	unlock := func() {
+		unlockSecond()
+		unlockFirst()
+	}
+	if firstId != envIdA {
+		return second, first, unlock, nil
+	}
+	return first, second, unlock, nil
+}
+
+// storeEnv saves the changes made to the copy of a locked env
+func storeEnv(envId uint64, env testenv.TestEnv) {
+	if item, ok := envRegister.Load(envId); ok {
+		item.(*envEntry).env = env
+	}
+}
+
+// removeEnv drops a locked env
+func removeEnv(envId uint64) {
+	if item, ok := envRegister.LoadAndDelete(envId); ok {
+		entry := item.(*envEntry)
+		entry.removed = true
+		entry.releaseLogs()
 	}
-	return env
 }
 
 func encodeErrToResultBytes(code byte, err error) *C.char {
@@ -480,10 +905,12 @@ func encodeBytesResultBytes(bytes []byte) *C.char {
 	return C.CString(result.EncodeResultFromOk(bytes))
 }
 
-func requireNoErr(err error) {
+func encodeJsonResultBytes(code byte, v any) *C.char {
+	bz, err := json.Marshal(v)
 	if err != nil {
-		panic(err)
+		return encodeErrToResultBytes(code, err)
 	}
+	return encodeBytesResultBytes(bz)
 }
 
 // must define main for ffi build
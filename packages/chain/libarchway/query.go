@@ -0,0 +1,85 @@
+package main
+
+import "C"
+
+import (
+	// std
+	"encoding/base64"
+	"fmt"
+
+	// helpers
+	proto "github.com/cosmos/gogoproto/proto"
+
+	// tendermint
+	abci "github.com/cometbft/cometbft/abci/types"
+
+	// cosmos sdk
+	storetypes "cosmossdk.io/store/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+
+	// cosmwasm-testing
+	"github.com/FloppyDisck/archway-test-tube/result"
+)
+
+// QuerySmartWithGas runs a smart query through the wasm keeper, like a contract querying
+// another one, and reports the gas it consumed. A gas limit of zero leaves the query unbounded.
+// The result is an ExecTxResult holding the response in Data.
+//
+//export QuerySmartWithGas
+func QuerySmartWithGas(envId uint64, bech32Contract, base64QueryMsgBytes string, gasLimit uint64) *C.char {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+	defer unlock()
+
+	contract, err := sdk.AccAddressFromBech32(bech32Contract)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+
+	queryMsgBytes, err := base64.StdEncoding.DecodeString(base64QueryMsgBytes)
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+
+	var gasMeter storetypes.GasMeter = storetypes.NewInfiniteGasMeter()
+	if gasLimit > 0 {
+		gasMeter = storetypes.NewGasMeter(gasLimit)
+	}
+	// Queries must not change the state
+	ctx, _ := env.Ctx.WithGasMeter(gasMeter).CacheContext()
+
+	data, err := recoverOutOfGas(gasMeter, func() ([]byte, error) {
+		return env.App.Keepers.WASMKeeper.QuerySmart(ctx, contract, queryMsgBytes)
+	})
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+
+	bz, err := proto.Marshal(&abci.ExecTxResult{
+		Data:      data,
+		GasWanted: int64(gasLimit),
+		GasUsed:   int64(gasMeter.GasConsumed()),
+	})
+	if err != nil {
+		return encodeErrToResultBytes(result.QueryError, err)
+	}
+
+	return encodeBytesResultBytes(bz)
+}
+
+// recoverOutOfGas turns running out of gas into an error
+func recoverOutOfGas(gasMeter storetypes.GasMeter, query func() ([]byte, error)) (data []byte, err error) {
+	defer func() {
+		if r := recover(); r != nil {
+			outOfGas, ok := r.(storetypes.ErrorOutOfGas)
+			if !ok {
+				panic(r)
+			}
+			err = fmt.Errorf("out of gas in location: %s; gasUsed: %d", outOfGas.Descriptor, gasMeter.GasConsumed())
+		}
+	}()
+
+	return query()
+}
@@ -0,0 +1,204 @@
+package testenv
+
+import (
+	// std
+	"bufio"
+	"fmt"
+	"os"
+	"regexp"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+
+	// helpers
+	"golang.org/x/sys/unix"
+
+	// tendermint
+	abci "github.com/cometbft/cometbft/abci/types"
+)
+
+// EventTypeContractDebug is the event attached to tx results when contract debug capture is enabled
+const EventTypeContractDebug = "litmus_contract_debug"
+
+// AttributeKeyMessages holds the json encoded DebugMessages of the tx
+const AttributeKeyMessages = "messages"
+
+// DebugMessage is a message printed by a contract with deps.api.debug
+type DebugMessage struct {
+	Message      string `json:"message"`
+	GasRemaining uint64 `json:"gas_remaining"`
+}
+
+// The contract debug mode, enabled by the trace flag, makes the VM print every message to stderr as
+// "[<time>]: <message> (gas remaining: <gas>)", a message may span several lines
+var (
+	debugStart = regexp.MustCompile(`^\[[^\]]*\]: `)
+	debugEnd   = regexp.MustCompile(`(?s)^\[[^\]]*\]: (.*) \(gas remaining: (\d+)\)$`)
+)
+
+// debugCapture points stderr to a pipe during each capture section, the sections run one at a time
+var debugCapture struct {
+	setup    sync.Once
+	setupErr error
+	section  sync.Mutex
+	// Write end of the pipe, which fd 2 points to during a section
+	pipe *os.File
+	// Duplicate of the original stderr
+	stderr  *os.File
+	markers chan string
+	counter uint64
+
+	// Shared with the goroutine reading the pipe
+	mu        sync.Mutex
+	capturing bool
+	messages  []DebugMessage
+}
+
+// WithContractDebug runs a block or a simulation, returning the debug messages printed by the contracts
+// while it ran if the env captures them
+func (env *TestEnv) WithContractDebug(run func() error) ([]DebugMessage, error) {
+	if !env.CaptureContractDebug {
+		return nil, run()
+	}
+
+	debugCapture.setup.Do(func() {
+		debugCapture.setupErr = openStderrPipe()
+	})
+	if debugCapture.setupErr != nil {
+		return nil, fmt.Errorf("capture contract debug messages: %w", debugCapture.setupErr)
+	}
+
+	debugCapture.section.Lock()
+	defer debugCapture.section.Unlock()
+
+	if err := unix.Dup2(int(debugCapture.pipe.Fd()), int(os.Stderr.Fd())); err != nil {
+		return nil, fmt.Errorf("redirect stderr: %w", err)
+	}
+	// Runs before the section is unlocked, the next one redirects stderr again
+	defer unix.Dup2(int(debugCapture.stderr.Fd()), int(os.Stderr.Fd()))
+
+	debugCapture.mu.Lock()
+	debugCapture.capturing = true
+	debugCapture.messages = nil
+	debugCapture.mu.Unlock()
+
+	runErr := run()
+
+	// The VM prints synchronously, so every message of the section is read once the marker is
+	debugCapture.counter++
+	marker := fmt.Sprintf("litmus-contract-debug-marker-%d", debugCapture.counter)
+	if _, err := fmt.Fprintln(debugCapture.pipe, marker); err == nil {
+		timeout := time.After(5 * time.Second)
+	wait:
+		for {
+			select {
+			case read := <-debugCapture.markers:
+				if read == marker {
+					break wait
+				}
+			case <-timeout:
+				break wait
+			}
+		}
+	}
+
+	debugCapture.mu.Lock()
+	defer debugCapture.mu.Unlock()
+
+	messages := debugCapture.messages
+	debugCapture.capturing = false
+	debugCapture.messages = nil
+
+	return messages, runErr
+}
+
+// AttachDebugMessages adds the debug messages event to the tx result. The messages printed while the
+// block ran can only be told apart when it has a single tx, as in the blocks of FinalizeBlock.
+func AttachDebugMessages(txResults []*abci.ExecTxResult, messages []DebugMessage) error {
+	if len(txResults) != 1 {
+		return nil
+	}
+	if messages == nil {
+		messages = []DebugMessage{}
+	}
+
+	return attachJSONEvent(txResults[0], EventTypeContractDebug, AttributeKeyMessages, messages)
+}
+
+// openStderrPipe creates the pipe the sections redirect stderr to and keeps a duplicate of the
+// original stderr to restore it and forward lines to it
+func openStderrPipe() error {
+	reader, writer, err := os.Pipe()
+	if err != nil {
+		return err
+	}
+
+	fd, err := unix.Dup(int(os.Stderr.Fd()))
+	if err != nil {
+		return err
+	}
+	stderr := os.NewFile(uintptr(fd), "stderr")
+
+	debugCapture.pipe = writer
+	debugCapture.stderr = stderr
+	debugCapture.markers = make(chan string, 16)
+	go readStderr(reader)
+
+	return nil
+}
+
+// readStderr sorts the lines written to stderr between debug messages, markers and forwarded lines
+func readStderr(reader *os.File) {
+	lines := bufio.NewReader(reader)
+	var pending []string
+
+	for {
+		line, err := lines.ReadString('\n')
+		if err != nil {
+			return
+		}
+		line = strings.TrimSuffix(line, "\n")
+
+		if strings.HasPrefix(line, "litmus-contract-debug-marker-") {
+			forward(pending)
+			pending = nil
+			select {
+			case debugCapture.markers <- line:
+			default:
+			}
+			continue
+		}
+
+		debugCapture.mu.Lock()
+		capturing := debugCapture.capturing
+		debugCapture.mu.Unlock()
+
+		if !capturing || (pending == nil && !debugStart.MatchString(line)) {
+			forward(append(pending, line))
+			pending = nil
+			continue
+		}
+
+		pending = append(pending, line)
+		match := debugEnd.FindStringSubmatch(strings.Join(pending, "\n"))
+		if match == nil {
+			continue
+		}
+		pending = nil
+
+		gas, err := strconv.ParseUint(match[2], 10, 64)
+		if err != nil {
+			continue
+		}
+		debugCapture.mu.Lock()
+		debugCapture.messages = append(debugCapture.messages, DebugMessage{Message: match[1], GasRemaining: gas})
+		debugCapture.mu.Unlock()
+	}
+}
+
+func forward(lines []string) {
+	for _, line := range lines {
+		fmt.Fprintln(debugCapture.stderr, line)
+	}
+}
@@ -0,0 +1,246 @@
+package testenv
+
+import (
+	// std
+	"encoding/json"
+	"fmt"
+	"reflect"
+	"strings"
+	"unsafe"
+
+	// tendermint
+	abci "github.com/cometbft/cometbft/abci/types"
+
+	// cosmos sdk
+	storetypes "cosmossdk.io/store/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+	authsigning "github.com/cosmos/cosmos-sdk/x/auth/signing"
+)
+
+// EventTypeGasBreakdown is the event attached to tx results when gas tracing is enabled
+const EventTypeGasBreakdown = "litmus_gas_breakdown"
+
+// AttributeKeyBreakdown holds the json encoded GasBreakdown of the tx
+const AttributeKeyBreakdown = "breakdown"
+
+type AnteGas struct {
+	Total                 uint64 `json:"total"`
+	TxSize                uint64 `json:"tx_size"`
+	SignatureVerification uint64 `json:"signature_verification"`
+}
+
+type WasmGas struct {
+	// Gas charged to load the contract before calling it
+	Setup   uint64 `json:"setup"`
+	Compile uint64 `json:"compile"`
+	// Gas reported by the VM for running the contract
+	Compute uint64 `json:"compute"`
+	// Gas charged for the events and attributes emitted by the contract
+	Events uint64 `json:"events"`
+}
+
+// StorageGas sums the KV store accesses, byte counts assume the default KV gas config
+type StorageGas struct {
+	Reads          uint64 `json:"reads"`
+	ReadBytes      uint64 `json:"read_bytes"`
+	ReadGas        uint64 `json:"read_gas"`
+	Writes         uint64 `json:"writes"`
+	WriteBytes     uint64 `json:"write_bytes"`
+	WriteGas       uint64 `json:"write_gas"`
+	Deletes        uint64 `json:"deletes"`
+	DeleteGas      uint64 `json:"delete_gas"`
+	Has            uint64 `json:"has"`
+	HasGas         uint64 `json:"has_gas"`
+	Iterations     uint64 `json:"iterations"`
+	IterationBytes uint64 `json:"iteration_bytes"`
+	IterationGas   uint64 `json:"iteration_gas"`
+}
+
+// GasBreakdown splits the gas used by a tx. The ante handler is measured as a whole, its tx size and
+// signature costs are derived from the auth params. Storage only covers the message execution.
+type GasBreakdown struct {
+	Total    uint64     `json:"total"`
+	Ante     AnteGas    `json:"ante"`
+	Wasm     WasmGas    `json:"wasm"`
+	Storage  StorageGas `json:"storage"`
+	Refunded uint64     `json:"refunded"`
+	Other    uint64     `json:"other"`
+}
+
+// GasTracer records the gas breakdown of the txs going through the ante handler, by tx hash
+type GasTracer struct {
+	Enabled bool
+	// Meters of the txs traced since the last reset
+	meters map[string]*tracingGasMeter
+}
+
+// Reset drops the traces of the previous txs
+func (t *GasTracer) Reset() {
+	t.meters = nil
+}
+
+// AttachBreakdowns adds the breakdown event to the tx results of the given txs. The txs rejected by
+// the ante handler never get a gas meter to trace, so their results have no breakdown.
+func (t *GasTracer) AttachBreakdowns(txs [][]byte, txResults []*abci.ExecTxResult) error {
+	if len(txs) != len(txResults) {
+		return fmt.Errorf("%d txs for %d tx results", len(txs), len(txResults))
+	}
+
+	for i, tx := range txs {
+		meter, ok := t.meters[txHash(tx)]
+		if !ok {
+			continue
+		}
+
+		if err := attachJSONEvent(txResults[i], EventTypeGasBreakdown, AttributeKeyBreakdown, meter.breakdown()); err != nil {
+			return err
+		}
+	}
+
+	return nil
+}
+
+// attachJSONEvent adds an event to the tx result holding the json encoded value as its only attribute
+func attachJSONEvent(txResult *abci.ExecTxResult, eventType, key string, value any) error {
+	bz, err := json.Marshal(value)
+	if err != nil {
+		return err
+	}
+
+	txResult.Events = append(txResult.Events, abci.Event{
+		Type:       eventType,
+		Attributes: []abci.EventAttribute{{Key: key, Value: string(bz)}},
+	})
+
+	return nil
+}
+
+// TraceGas wraps the ante handler of the app, so the gas meter of every tx it sets up is traced
+// and the store operations it does are told apart from the ones of the messages.
+// The app is sealed once loaded, so the handler is swapped in place.
+func (env *TestEnv) TraceGas() {
+	field := anteHandlerField(env)
+
+	anteHandler := field.Interface().(sdk.AnteHandler)
+	tracer := env.GasTracer
+	storeTracer := env.StoreTracer
+	bypass := env.AnteBypass
+	accountKeeper := env.App.Keepers.AccountKeeper
+
+	var traced sdk.AnteHandler = func(ctx sdk.Context, tx sdk.Tx, simulate bool) (sdk.Context, error) {
+		storeTracer.inAnte = true
+		newCtx, err := anteHandler(ctx, tx, simulate)
+		storeTracer.inAnte = false
+		if err != nil || !tracer.Enabled {
+			return newCtx, err
+		}
+
+		ante := AnteGas{Total: newCtx.GasMeter().GasConsumed()}
+		if !bypass.Skips(ctx.TxBytes()) {
+			params := accountKeeper.GetParams(ctx)
+			ante.TxSize = params.TxSizeCostPerByte * uint64(len(ctx.TxBytes()))
+			if sigTx, ok := tx.(authsigning.SigVerifiableTx); ok {
+				if sigs, err := sigTx.GetSignaturesV2(); err == nil {
+					ante.SignatureVerification = params.SigVerifyCostSecp256k1 * uint64(len(sigs))
+				}
+			}
+		}
+
+		meter := &tracingGasMeter{GasMeter: newCtx.GasMeter(), ante: ante, config: storetypes.KVGasConfig()}
+		if tracer.meters == nil {
+			tracer.meters = map[string]*tracingGasMeter{}
+		}
+		tracer.meters[txHash(ctx.TxBytes())] = meter
+
+		return newCtx.WithGasMeter(meter), nil
+	}
+
+	field.Set(reflect.ValueOf(traced))
+}
+
+// anteHandlerField returns the settable ante handler of the app, which is sealed once loaded
+func anteHandlerField(env *TestEnv) reflect.Value {
+	field := reflect.ValueOf(env.App.BaseApp).Elem().FieldByName("anteHandler")
+	return reflect.NewAt(field.Type(), unsafe.Pointer(field.UnsafeAddr())).Elem()
+}
+
+// tracingGasMeter sorts the gas consumed after the ante handler by its descriptor
+type tracingGasMeter struct {
+	storetypes.GasMeter
+	ante     AnteGas
+	config   storetypes.GasConfig
+	wasm     WasmGas
+	storage  StorageGas
+	refunded uint64
+}
+
+var _ storetypes.GasMeter = &tracingGasMeter{}
+
+func (m *tracingGasMeter) ConsumeGas(amount storetypes.Gas, descriptor string) {
+	m.GasMeter.ConsumeGas(amount, descriptor)
+
+	switch {
+	case descriptor == storetypes.GasReadCostFlatDesc:
+		m.storage.Reads++
+		m.storage.ReadGas += amount
+	case descriptor == storetypes.GasReadPerByteDesc:
+		m.storage.ReadGas += amount
+		m.storage.ReadBytes += perByte(amount, m.config.ReadCostPerByte)
+	case descriptor == storetypes.GasWriteCostFlatDesc:
+		m.storage.Writes++
+		m.storage.WriteGas += amount
+	case descriptor == storetypes.GasWritePerByteDesc:
+		m.storage.WriteGas += amount
+		m.storage.WriteBytes += perByte(amount, m.config.WriteCostPerByte)
+	case descriptor == storetypes.GasDeleteDesc:
+		m.storage.Deletes++
+		m.storage.DeleteGas += amount
+	case descriptor == storetypes.GasHasDesc:
+		m.storage.Has++
+		m.storage.HasGas += amount
+	case descriptor == storetypes.GasIterNextCostFlatDesc:
+		m.storage.Iterations++
+		m.storage.IterationGas += amount
+	case descriptor == storetypes.GasValuePerByteDesc:
+		m.storage.IterationGas += amount
+		m.storage.IterationBytes += perByte(amount, m.config.ReadCostPerByte)
+	case descriptor == "wasm contract":
+		m.wasm.Compute += amount
+	case strings.HasPrefix(descriptor, "Loading CosmWasm module"):
+		m.wasm.Setup += amount
+	case strings.HasPrefix(descriptor, "Compiling wasm"):
+		m.wasm.Compile += amount
+	case strings.Contains(descriptor, "event"):
+		m.wasm.Events += amount
+	}
+}
+
+func (m *tracingGasMeter) RefundGas(amount storetypes.Gas, descriptor string) {
+	m.GasMeter.RefundGas(amount, descriptor)
+	m.refunded += amount
+}
+
+func (m *tracingGasMeter) breakdown() GasBreakdown {
+	breakdown := GasBreakdown{
+		Total:    m.GasConsumed(),
+		Ante:     m.ante,
+		Wasm:     m.wasm,
+		Storage:  m.storage,
+		Refunded: m.refunded,
+	}
+
+	sorted := m.ante.Total + m.wasm.Setup + m.wasm.Compile + m.wasm.Compute + m.wasm.Events +
+		m.storage.ReadGas + m.storage.WriteGas + m.storage.DeleteGas + m.storage.HasGas + m.storage.IterationGas
+	if consumed := breakdown.Total + m.refunded; consumed > sorted {
+		breakdown.Other = consumed - sorted
+	}
+
+	return breakdown
+}
+
+func perByte(amount, costPerByte storetypes.Gas) uint64 {
+	if costPerByte == 0 {
+		return 0
+	}
+	return amount / costPerByte
+}
@@ -0,0 +1,208 @@
+package testenv
+
+import (
+	"encoding/hex"
+	"fmt"
+	"strconv"
+
+	// tendermint
+	abci "github.com/cometbft/cometbft/abci/types"
+	"github.com/cometbft/cometbft/crypto/tmhash"
+	cmtproto "github.com/cometbft/cometbft/proto/tendermint/types"
+	cmtversion "github.com/cometbft/cometbft/proto/tendermint/version"
+	tmtypes "github.com/cometbft/cometbft/types"
+	"github.com/cometbft/cometbft/version"
+
+	// cosmos sdk
+	sdk "github.com/cosmos/cosmos-sdk/types"
+
+	// ibc
+	clienttypes "github.com/cosmos/ibc-go/v8/modules/core/02-client/types"
+	channeltypes "github.com/cosmos/ibc-go/v8/modules/core/04-channel/types"
+	ibctm "github.com/cosmos/ibc-go/v8/modules/light-clients/07-tendermint"
+)
+
+// PacketAck is the acknowledgement written by the chain for a received packet
+type PacketAck struct {
+	Packet channeltypes.Packet
+	Ack    []byte
+}
+
+// unusedHash fills the header fields the light client does not verify
+var unusedHash = tmhash.Sum([]byte{0x00})
+
+// ValidatorSet rebuilds the tendermint validator set from the genesis validator keys
+func (env *TestEnv) ValidatorSet() (*tmtypes.ValidatorSet, error) {
+	validators := make([]*tmtypes.Validator, 0, len(env.ValPrivs))
+	for _, valPriv := range env.ValPrivs {
+		pubKey, err := PV{PrivKey: valPriv}.GetPubKey()
+		if err != nil {
+			return nil, err
+		}
+		validators = append(validators, tmtypes.NewValidator(pubKey, 1))
+	}
+
+	return tmtypes.NewValidatorSet(validators), nil
+}
+
+// RelayerAddress is the account signing the messages delivered by the relayer
+func (env *TestEnv) RelayerAddress() string {
+	return sdk.AccAddress(env.ValPrivs[0].PubKey().Address()).String()
+}
+
+// Revision is the IBC revision number encoded in the chain id
+func (env *TestEnv) Revision() uint64 {
+	return clienttypes.ParseChainID(env.Ctx.ChainID())
+}
+
+// LightClientHeader signs a header for the latest committed block. The time and app hash are
+// taken from the staking historical info, so they match the consensus state the chain
+// expects counterparties to hold of it during connection handshakes.
+func (env *TestEnv) LightClientHeader(trustedHeight clienttypes.Height) (*ibctm.Header, error) {
+	height := env.Ctx.BlockHeight()
+	histInfo, err := env.App.Keepers.StakingKeeper.GetHistoricalInfo(env.Ctx, height)
+	if err != nil {
+		return nil, err
+	}
+
+	valSet, err := env.ValidatorSet()
+	if err != nil {
+		return nil, err
+	}
+
+	chainID := env.Ctx.ChainID()
+	header := tmtypes.Header{
+		Version:            cmtversion.Consensus{Block: version.BlockProtocol},
+		ChainID:            chainID,
+		Height:             height,
+		Time:               histInfo.Header.Time,
+		LastBlockID:        tmtypes.BlockID{Hash: make([]byte, tmhash.Size), PartSetHeader: tmtypes.PartSetHeader{Total: 1, Hash: make([]byte, tmhash.Size)}},
+		LastCommitHash:     unusedHash,
+		DataHash:           unusedHash,
+		ValidatorsHash:     valSet.Hash(),
+		NextValidatorsHash: valSet.Hash(),
+		ConsensusHash:      unusedHash,
+		AppHash:            histInfo.Header.AppHash,
+		LastResultsHash:    unusedHash,
+		EvidenceHash:       unusedHash,
+		ProposerAddress:    valSet.Proposer.Address,
+	}
+	blockID := tmtypes.BlockID{Hash: header.Hash(), PartSetHeader: tmtypes.PartSetHeader{Total: 1, Hash: unusedHash}}
+
+	// Signers must follow the order of the validator set
+	signersByAddress := map[string]tmtypes.PrivValidator{}
+	for _, valPriv := range env.ValPrivs {
+		pv := PV{PrivKey: valPriv}
+		pubKey, err := pv.GetPubKey()
+		if err != nil {
+			return nil, err
+		}
+		signersByAddress[pubKey.Address().String()] = pv
+	}
+	signers := make([]tmtypes.PrivValidator, 0, len(valSet.Validators))
+	for _, validator := range valSet.Validators {
+		signers = append(signers, signersByAddress[validator.Address.String()])
+	}
+
+	voteSet := tmtypes.NewVoteSet(chainID, height, 1, cmtproto.PrecommitType, valSet)
+	commit, err := tmtypes.MakeExtCommit(blockID, height, 1, voteSet, signers, header.Time, false)
+	if err != nil {
+		return nil, err
+	}
+
+	protoValSet, err := valSet.ToProto()
+	if err != nil {
+		return nil, err
+	}
+
+	return &ibctm.Header{
+		SignedHeader: &cmtproto.SignedHeader{
+			Header: header.ToProto(),
+			Commit: commit.ToCommit().ToProto(),
+		},
+		ValidatorSet:      protoValSet,
+		TrustedHeight:     trustedHeight,
+		TrustedValidators: protoValSet,
+	}, nil
+}
+
+// TrackIbcEvents records the packets sent and the acknowledgements written by the chain
+func (env *TestEnv) TrackIbcEvents(events []abci.Event) {
+	for _, event := range events {
+		switch event.Type {
+		case channeltypes.EventTypeSendPacket:
+			packet, err := parsePacket(event)
+			if err != nil {
+				continue
+			}
+			env.PendingPackets = append(env.PendingPackets, packet)
+		case channeltypes.EventTypeWriteAck:
+			packet, err := parsePacket(event)
+			if err != nil {
+				continue
+			}
+			ack, err := hex.DecodeString(eventAttribute(event, channeltypes.AttributeKeyAckHex))
+			if err != nil {
+				continue
+			}
+			env.PendingAcks = append(env.PendingAcks, PacketAck{Packet: packet, Ack: ack})
+		}
+	}
+}
+
+func parsePacket(event abci.Event) (channeltypes.Packet, error) {
+	data, err := hex.DecodeString(eventAttribute(event, channeltypes.AttributeKeyDataHex))
+	if err != nil {
+		return channeltypes.Packet{}, err
+	}
+
+	sequence, err := strconv.ParseUint(eventAttribute(event, channeltypes.AttributeKeySequence), 10, 64)
+	if err != nil {
+		return channeltypes.Packet{}, err
+	}
+
+	timeoutHeight, err := clienttypes.ParseHeight(eventAttribute(event, channeltypes.AttributeKeyTimeoutHeight))
+	if err != nil {
+		return channeltypes.Packet{}, err
+	}
+
+	timeoutTimestamp, err := strconv.ParseUint(eventAttribute(event, channeltypes.AttributeKeyTimeoutTimestamp), 10, 64)
+	if err != nil {
+		return channeltypes.Packet{}, err
+	}
+
+	return channeltypes.NewPacket(
+		data,
+		sequence,
+		eventAttribute(event, channeltypes.AttributeKeySrcPort),
+		eventAttribute(event, channeltypes.AttributeKeySrcChannel),
+		eventAttribute(event, channeltypes.AttributeKeyDstPort),
+		eventAttribute(event, channeltypes.AttributeKeyDstChannel),
+		timeoutHeight,
+		timeoutTimestamp,
+	), nil
+}
+
+// EventAttribute returns the value of the first attribute with the given key in the events of the given type
+func EventAttribute(events []abci.Event, eventType, key string) (string, error) {
+	for _, event := range events {
+		if event.Type != eventType {
+			continue
+		}
+		if value := eventAttribute(event, key); value != "" {
+			return value, nil
+		}
+	}
+
+	return "", fmt.Errorf("attribute %s not found in %s events", key, eventType)
+}
+
+func eventAttribute(event abci.Event, key string) string {
+	for _, attr := range event.Attributes {
+		if attr.Key == key {
+			return attr.Value
+		}
+	}
+
+	return ""
+}
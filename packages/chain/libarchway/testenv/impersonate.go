@@ -0,0 +1,56 @@
+package testenv
+
+import (
+	// std
+	"reflect"
+
+	// cosmos sdk
+	storetypes "cosmossdk.io/store/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+)
+
+// AnteBypass holds the txs that skip the ante handler, by tx hash
+type AnteBypass struct {
+	txs map[string]bool
+}
+
+// Add makes the tx skip the ante handler until it is removed
+func (b *AnteBypass) Add(tx []byte) {
+	if b.txs == nil {
+		b.txs = map[string]bool{}
+	}
+	b.txs[txHash(tx)] = true
+}
+
+func (b *AnteBypass) Remove(tx []byte) {
+	delete(b.txs, txHash(tx))
+}
+
+// Skips tells whether the tx skips the ante handler
+func (b *AnteBypass) Skips(tx []byte) bool {
+	return b.txs[txHash(tx)]
+}
+
+// BypassAnte wraps the ante handler of the app, so the txs added to the bypass run their messages
+// in the block without any signature, sequence or fee check. The gas they can use is only bounded
+// by the block gas limit. It must wrap the handler before TraceGas, which then traces these txs too.
+func (env *TestEnv) BypassAnte() {
+	field := anteHandlerField(env)
+
+	anteHandler := field.Interface().(sdk.AnteHandler)
+	bypass := env.AnteBypass
+
+	var bypassed sdk.AnteHandler = func(ctx sdk.Context, tx sdk.Tx, simulate bool) (sdk.Context, error) {
+		if !bypass.Skips(ctx.TxBytes()) {
+			return anteHandler(ctx, tx, simulate)
+		}
+
+		var gasMeter storetypes.GasMeter = storetypes.NewInfiniteGasMeter()
+		if block := ctx.ConsensusParams().Block; block != nil && block.MaxGas > 0 {
+			gasMeter = storetypes.NewGasMeter(uint64(block.MaxGas))
+		}
+		return ctx.WithGasMeter(gasMeter), nil
+	}
+
+	field.Set(reflect.ValueOf(bypassed))
+}
@@ -0,0 +1,55 @@
+package testenv
+
+import (
+	// std
+	"bytes"
+	"encoding/json"
+	"sync"
+
+	// helpers
+	"github.com/rs/zerolog"
+)
+
+// LogCapture keeps the json encoded log entries of an env until they are taken
+type LogCapture struct {
+	mu      sync.Mutex
+	level   zerolog.Level
+	entries []json.RawMessage
+}
+
+// NewLogCapture returns a capture that keeps nothing until its level is set
+func NewLogCapture() *LogCapture {
+	return &LogCapture{level: zerolog.Disabled}
+}
+
+// SetLevel sets the lowest level kept, zerolog.Disabled stops the capture
+func (c *LogCapture) SetLevel(level zerolog.Level) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+
+	c.level = level
+}
+
+// Capture keeps the entry if its level is high enough, the logger reuses its buffer so it is copied
+func (c *LogCapture) Capture(level zerolog.Level, entry []byte) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+
+	if c.level == zerolog.Disabled || level < c.level {
+		return
+	}
+	c.entries = append(c.entries, bytes.Clone(bytes.TrimSpace(entry)))
+}
+
+// Take returns the entries captured since the last call
+func (c *LogCapture) Take() []json.RawMessage {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+
+	entries := c.entries
+	c.entries = nil
+	if entries == nil {
+		return []json.RawMessage{}
+	}
+	return entries
+}
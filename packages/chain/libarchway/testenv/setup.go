@@ -40,6 +40,8 @@ import (
 
 	tmtypes "github.com/cometbft/cometbft/types"
 
+	channeltypes "github.com/cosmos/ibc-go/v8/modules/core/04-channel/types"
+
 	codectypes "github.com/cosmos/cosmos-sdk/codec/types"
 
 	cryptocodec "github.com/cosmos/cosmos-sdk/crypto/codec"
@@ -49,7 +51,7 @@ import (
 	banktypes "github.com/cosmos/cosmos-sdk/x/bank/types"
 )
 
-func GenesisStateWithValSet(appInstance *app.ArchwayApp) (app.GenesisState, secp256k1.PrivKey) {
+func GenesisStateWithValSet(appInstance *app.ArchwayApp) (app.GenesisState, secp256k1.PrivKey, error) {
 	privVal := NewPV()
 	pubKey, _ := privVal.GetPubKey()
 	validator := tmtypes.NewValidator(pubKey, 1)
@@ -156,10 +158,10 @@ func GenesisStateWithValSet(appInstance *app.ArchwayApp) (app.GenesisState, secp
 
 	_, err := tmtypes.PB2TM.ValidatorUpdates(initValPowers)
 	if err != nil {
-		panic("failed to get vals")
+		return nil, secp256k1.PrivKey{}, errors.Wrap(err, "failed to get vals")
 	}
 
-	return genesisState, secp256k1.PrivKey{Key: privVal.PrivKey.Bytes()}
+	return genesisState, secp256k1.PrivKey{Key: privVal.PrivKey.Bytes()}, nil
 }
 
 type TestEnv struct {
@@ -168,6 +170,19 @@ type TestEnv struct {
 	ParamTypesRegistry ParamTypeRegistry
 	ValPrivs           []*secp256k1.PrivKey
 	NodeHome           string
+	// IBC packets and acknowledgements waiting to be relayed
+	PendingPackets []channeltypes.Packet
+	PendingAcks    []PacketAck
+	// Shared with the ante handler wrapped by TraceGas
+	GasTracer *GasTracer
+	// Shared with the ante handler wrapped by BypassAnte
+	AnteBypass *AnteBypass
+	// Shared with the multistore wrapped by TraceStorage
+	StoreTracer *StoreTracer
+	// Shared with the logger of the app
+	Logs *LogCapture
+	// Attach the debug messages printed by the contracts to the tx results
+	CaptureContractDebug bool
 }
 
 // DebugAppOptions is a stub implementing AppOptions
@@ -185,11 +200,11 @@ func (ao DebugAppOptions) Get(o string) interface{} {
 	return nil
 }
 
-func NewArchwayApp(nodeHome string) *app.ArchwayApp {
+func NewArchwayApp(nodeHome string, chainID string, logger log.Logger) *app.ArchwayApp {
 	db := dbm.NewMemDB()
 
 	return app.NewArchwayApp(
-		log.NewNopLogger(),
+		logger,
 		db,
 		nil,
 		true,
@@ -199,13 +214,15 @@ func NewArchwayApp(nodeHome string) *app.ArchwayApp {
 		app.MakeEncodingConfig(),
 		DebugAppOptions{},
 		[]wasmdKeeper.Option{},
-		baseapp.SetChainID("archway-1"),
+		baseapp.SetChainID(chainID),
 	)
 }
 
-func InitChain(appInstance *app.ArchwayApp) (sdk.Context, secp256k1.PrivKey) {
-	sdk.DefaultBondDenom = "aarch"
-	genesisState, valPriv := GenesisStateWithValSet(appInstance)
+func InitChain(appInstance *app.ArchwayApp, chainID string) (sdk.Context, secp256k1.PrivKey, error) {
+	genesisState, valPriv, err := GenesisStateWithValSet(appInstance)
+	if err != nil {
+		return sdk.Context{}, valPriv, err
+	}
 
 	encCfg := app.MakeEncodingConfig()
 
@@ -224,8 +241,9 @@ func InitChain(appInstance *app.ArchwayApp) (sdk.Context, secp256k1.PrivKey) {
 	appInstance.AppCodec().UnmarshalJSON(genesisState[stakingtypes.ModuleName], &stakingGenesisState)
 
 	stateBytes, err := json.MarshalIndent(genesisState, "", " ")
-
-	requireNoErr(err)
+	if err != nil {
+		return sdk.Context{}, valPriv, err
+	}
 
 	concensusParams := simtestutil.DefaultConsensusParams
 	concensusParams.Block = &cmtproto.BlockParams{
@@ -241,19 +259,21 @@ func InitChain(appInstance *app.ArchwayApp) (sdk.Context, secp256k1.PrivKey) {
 			Validators:      []abci.ValidatorUpdate{},
 			ConsensusParams: concensusParams,
 			AppStateBytes:   stateBytes,
-			ChainId:         "archway-1",
+			ChainId:         chainID,
 		},
 	)
 	if err != nil {
-		panic(err)
+		return sdk.Context{}, valPriv, err
 	}
 
-	ctx := appInstance.NewContextLegacy(false, cmtproto.Header{Height: 0, ChainID: "archway-1", Time: time.Now().UTC()})
+	ctx := appInstance.NewContextLegacy(false, cmtproto.Header{Height: 0, ChainID: chainID, Time: time.Now().UTC()})
 
 	// for each stakingGenesisState.Validators
 	for _, validator := range stakingGenesisState.Validators {
 		consAddr, err := validator.GetConsAddr()
-		requireNoErr(err)
+		if err != nil {
+			return sdk.Context{}, valPriv, err
+		}
 		signingInfo := slashingtypes.NewValidatorSigningInfo(
 			consAddr,
 			ctx.BlockHeight(),
@@ -264,30 +284,38 @@ func InitChain(appInstance *app.ArchwayApp) (sdk.Context, secp256k1.PrivKey) {
 		)
 		err = appInstance.Keepers.SlashingKeeper.SetValidatorSigningInfo(ctx, consAddr, signingInfo)
 		if err != nil {
-			panic(err)
+			return sdk.Context{}, valPriv, err
 		}
 	}
 
-	return ctx, valPriv
+	return ctx, valPriv, nil
 }
 
-func (env *TestEnv) BeginNewBlock(executeNextEpoch bool, timeIncreaseSeconds uint64) {
+func (env *TestEnv) BeginNewBlock(executeNextEpoch bool, timeIncreaseSeconds uint64) error {
 	validators, err := env.App.Keepers.StakingKeeper.GetAllValidators(env.Ctx)
-	requireNoErr(err)
+	if err != nil {
+		return err
+	}
+	if len(validators) == 0 {
+		return fmt.Errorf("no validators found")
+	}
 	valAddr, err := validators[0].GetConsAddr()
-	requireNoErr(err)
+	if err != nil {
+		return err
+	}
 
-	env.beginNewBlockWithProposer(executeNextEpoch, valAddr, timeIncreaseSeconds)
+	return env.beginNewBlockWithProposer(executeNextEpoch, valAddr, timeIncreaseSeconds)
 }
 
-func (env *TestEnv) FundValidators() {
+func (env *TestEnv) FundValidators() error {
 	for _, valPriv := range env.ValPrivs {
 		valAddr := sdk.AccAddress(valPriv.PubKey().Address())
 		err := banktestutil.FundAccount(env.Ctx, env.App.Keepers.BankKeeper, valAddr.Bytes(), sdk.NewCoins(sdk.NewInt64Coin("aarch", 9223372036854775807)))
 		if err != nil {
-			panic(errors.Wrapf(err, "Failed to fund account"))
+			return errors.Wrapf(err, "Failed to fund account")
 		}
 	}
+	return nil
 }
 
 func (env *TestEnv) GetValidatorAddresses() []string {
@@ -301,12 +329,16 @@ func (env *TestEnv) GetValidatorAddresses() []string {
 }
 
 // beginNewBlockWithProposer begins a new block with a proposer.
-func (env *TestEnv) beginNewBlockWithProposer(executeNextEpoch bool, proposer sdk.ValAddress, timeIncreaseSeconds uint64) {
+func (env *TestEnv) beginNewBlockWithProposer(executeNextEpoch bool, proposer sdk.ValAddress, timeIncreaseSeconds uint64) error {
 	validator, err := env.App.Keepers.StakingKeeper.GetValidator(env.Ctx, proposer)
-	requireNoErr(err)
+	if err != nil {
+		return err
+	}
 
 	valConsAddr, err := validator.GetConsAddr()
-	requireNoErr(err)
+	if err != nil {
+		return err
+	}
 
 	valAddr := valConsAddr
 
@@ -321,9 +353,12 @@ func (env *TestEnv) beginNewBlockWithProposer(executeNextEpoch bool, proposer sd
 	env.Ctx = env.Ctx.WithVoteInfos(voteInfos)
 
 	_, err = env.App.BeginBlocker(env.Ctx)
-	requireNoErr(err)
+	if err != nil {
+		return err
+	}
 
 	env.Ctx = env.App.NewContextLegacy(false, header)
+	return nil
 }
 
 func (env *TestEnv) SetupParamTypes() {
@@ -331,21 +366,3 @@ func (env *TestEnv) SetupParamTypes() {
 
 	pReg.RegisterParamSet(&rewards.Params{})
 }
-
-func requireNoErr(err error) {
-	if err != nil {
-		panic(err)
-	}
-}
-
-func requireNoNil(name string, nilable any) {
-	if nilable == nil {
-		panic(fmt.Sprintf("%s must not be nil", name))
-	}
-}
-
-func requierTrue(name string, b bool) {
-	if !b {
-		panic(fmt.Sprintf("%s must be true", name))
-	}
-}
@@ -0,0 +1,243 @@
+package testenv
+
+import (
+	// std
+	"fmt"
+	"reflect"
+	"unsafe"
+
+	// tendermint
+	abci "github.com/cometbft/cometbft/abci/types"
+	"github.com/cometbft/cometbft/crypto/tmhash"
+
+	// cosmos sdk
+	storetypes "cosmossdk.io/store/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+
+	// wasmd
+	wasmtypes "github.com/CosmWasm/wasmd/x/wasm/types"
+)
+
+// EventTypeStorageTrace is the event attached to tx results when storage tracing is enabled
+const EventTypeStorageTrace = "litmus_storage_trace"
+
+// AttributeKeyAccesses holds the json encoded StoreAccess list of the tx
+const AttributeKeyAccesses = "accesses"
+
+// Length of the contract addresses prefixing their storage
+const contractAddressLength = 32
+
+// StoreAccess is a single KV store operation performed by a tx
+type StoreAccess struct {
+	// read, write, delete, has or iterate
+	Operation string `json:"operation"`
+	Store     string `json:"store"`
+	Key       []byte `json:"key"`
+	// Set when the key belongs to the storage of a contract
+	Contract    string `json:"contract,omitempty"`
+	ContractKey []byte `json:"contract_key,omitempty"`
+	ValueSize   int    `json:"value_size"`
+	// Gas charged by the store for the operation
+	Gas uint64 `json:"gas"`
+	// Whether the operation was done by the ante handler
+	Ante bool `json:"ante"`
+}
+
+// StoreTracer records the KV store operations of the txs, grouped by tx hash
+type StoreTracer struct {
+	Enabled  bool
+	inAnte   bool
+	accesses map[string][]StoreAccess
+}
+
+// Reset drops the operations of the previous txs
+func (t *StoreTracer) Reset() {
+	t.accesses = nil
+}
+
+// AttachAccesses adds the trace event to the tx results of the given txs
+func (t *StoreTracer) AttachAccesses(txs [][]byte, txResults []*abci.ExecTxResult) error {
+	if len(txs) != len(txResults) {
+		return nil
+	}
+
+	for i, tx := range txs {
+		accesses := t.accesses[txHash(tx)]
+		if accesses == nil {
+			accesses = []StoreAccess{}
+		}
+
+		if err := attachJSONEvent(txResults[i], EventTypeStorageTrace, AttributeKeyAccesses, accesses); err != nil {
+			return err
+		}
+	}
+
+	return nil
+}
+
+func (t *StoreTracer) record(hash string, access StoreAccess) {
+	if t.accesses == nil {
+		t.accesses = map[string][]StoreAccess{}
+	}
+
+	access.Ante = t.inAnte
+	if access.Store == wasmtypes.StoreKey && len(access.Key) > len(wasmtypes.ContractStorePrefix)+contractAddressLength &&
+		access.Key[0] == wasmtypes.ContractStorePrefix[0] {
+		address := access.Key[len(wasmtypes.ContractStorePrefix) : len(wasmtypes.ContractStorePrefix)+contractAddressLength]
+		access.Contract = sdk.AccAddress(address).String()
+		access.ContractKey = access.Key[len(wasmtypes.ContractStorePrefix)+contractAddressLength:]
+	}
+
+	t.accesses[hash] = append(t.accesses[hash], access)
+}
+
+// txHash matches the tracing context baseapp sets on the stores of a tx
+func txHash(tx []byte) string {
+	return fmt.Sprintf("%X", tmhash.Sum(tx))
+}
+
+// TraceStorage wraps the commit multistore of the app, so the stores of every tx record their operations.
+// The app is sealed once loaded, so the multistore is swapped in place.
+func (env *TestEnv) TraceStorage() {
+	field := reflect.ValueOf(env.App.BaseApp).Elem().FieldByName("cms")
+	field = reflect.NewAt(field.Type(), unsafe.Pointer(field.UnsafeAddr())).Elem()
+
+	cms := field.Interface().(storetypes.CommitMultiStore)
+	field.Set(reflect.ValueOf(storetypes.CommitMultiStore(&tracingCommitMultiStore{
+		CommitMultiStore: cms,
+		tracer:           env.StoreTracer,
+	})))
+}
+
+type tracingCommitMultiStore struct {
+	storetypes.CommitMultiStore
+	tracer *StoreTracer
+}
+
+func (cms *tracingCommitMultiStore) CacheMultiStore() storetypes.CacheMultiStore {
+	return &tracingCacheMultiStore{CacheMultiStore: cms.CommitMultiStore.CacheMultiStore(), tracer: cms.tracer}
+}
+
+// Query keeps the proofs queried through the app working
+func (cms *tracingCommitMultiStore) Query(req *storetypes.RequestQuery) (*storetypes.ResponseQuery, error) {
+	queryable, ok := cms.CommitMultiStore.(storetypes.Queryable)
+	if !ok {
+		return nil, fmt.Errorf("multistore is not queryable")
+	}
+	return queryable.Query(req)
+}
+
+// tracingCacheMultiStore traces the stores of a tx once baseapp sets its hash as tracing context
+type tracingCacheMultiStore struct {
+	storetypes.CacheMultiStore
+	tracer *StoreTracer
+	txHash string
+}
+
+func (ms *tracingCacheMultiStore) CacheMultiStore() storetypes.CacheMultiStore {
+	return &tracingCacheMultiStore{CacheMultiStore: ms.CacheMultiStore.CacheMultiStore(), tracer: ms.tracer, txHash: ms.txHash}
+}
+
+// TracingEnabled makes baseapp set the tx hash as tracing context
+func (ms *tracingCacheMultiStore) TracingEnabled() bool {
+	return true
+}
+
+func (ms *tracingCacheMultiStore) SetTracingContext(tc storetypes.TraceContext) storetypes.MultiStore {
+	if hash, ok := tc["txHash"].(string); ok {
+		ms.txHash = hash
+	}
+	return ms
+}
+
+func (ms *tracingCacheMultiStore) GetStore(key storetypes.StoreKey) storetypes.Store {
+	return ms.GetKVStore(key)
+}
+
+func (ms *tracingCacheMultiStore) GetKVStore(key storetypes.StoreKey) storetypes.KVStore {
+	store := ms.CacheMultiStore.GetKVStore(key)
+	if !ms.tracer.Enabled || ms.txHash == "" {
+		return store
+	}
+
+	config := storetypes.KVGasConfig()
+	if _, ok := key.(*storetypes.TransientStoreKey); ok {
+		config = storetypes.TransientGasConfig()
+	}
+
+	return &tracingKVStore{KVStore: store, name: key.Name(), config: config, tracer: ms.tracer, txHash: ms.txHash}
+}
+
+// tracingKVStore sits right below the gas store, so it sees every operation charged to the tx
+type tracingKVStore struct {
+	storetypes.KVStore
+	name   string
+	config storetypes.GasConfig
+	tracer *StoreTracer
+	txHash string
+}
+
+func (s *tracingKVStore) record(operation string, key []byte, valueSize int, gas uint64) {
+	s.tracer.record(s.txHash, StoreAccess{
+		Operation: operation,
+		Store:     s.name,
+		Key:       append([]byte{}, key...),
+		ValueSize: valueSize,
+		Gas:       gas,
+	})
+}
+
+func (s *tracingKVStore) Get(key []byte) []byte {
+	value := s.KVStore.Get(key)
+	s.record("read", key, len(value), s.config.ReadCostFlat+s.config.ReadCostPerByte*uint64(len(key)+len(value)))
+	return value
+}
+
+func (s *tracingKVStore) Has(key []byte) bool {
+	s.record("has", key, 0, s.config.HasCost)
+	return s.KVStore.Has(key)
+}
+
+func (s *tracingKVStore) Set(key, value []byte) {
+	s.record("write", key, len(value), s.config.WriteCostFlat+s.config.WriteCostPerByte*uint64(len(key)+len(value)))
+	s.KVStore.Set(key, value)
+}
+
+func (s *tracingKVStore) Delete(key []byte) {
+	s.record("delete", key, 0, s.config.DeleteCost)
+	s.KVStore.Delete(key)
+}
+
+func (s *tracingKVStore) Iterator(start, end []byte) storetypes.Iterator {
+	return newTracingIterator(s, s.KVStore.Iterator(start, end))
+}
+
+func (s *tracingKVStore) ReverseIterator(start, end []byte) storetypes.Iterator {
+	return newTracingIterator(s, s.KVStore.ReverseIterator(start, end))
+}
+
+// tracingIterator records every entry it reaches, like the gas store charges them
+type tracingIterator struct {
+	storetypes.Iterator
+	store *tracingKVStore
+}
+
+func newTracingIterator(store *tracingKVStore, parent storetypes.Iterator) storetypes.Iterator {
+	iterator := &tracingIterator{Iterator: parent, store: store}
+	iterator.recordEntry()
+	return iterator
+}
+
+func (it *tracingIterator) Next() {
+	it.Iterator.Next()
+	it.recordEntry()
+}
+
+func (it *tracingIterator) recordEntry() {
+	if !it.Valid() {
+		return
+	}
+
+	key, value := it.Key(), it.Value()
+	it.store.record("iterate", key, len(value), it.store.config.IterNextCostFlat+it.store.config.ReadCostPerByte*uint64(len(key)+len(value)))
+}
@@ -0,0 +1,68 @@
+package main
+
+import "C"
+
+import (
+	// std
+	"fmt"
+
+	// helpers
+	"github.com/pkg/errors"
+
+	// archway
+	"github.com/archway-network/archway/app"
+
+	// cosmwasm-testing
+	"github.com/FloppyDisck/archway-test-tube/result"
+)
+
+// ApplyUpgrade commits empty blocks up to the height of the scheduled upgrade, the upgrade module
+// running its handler at the start of that block. It returns the height as json.
+//
+//export ApplyUpgrade
+func ApplyUpgrade(envId uint64, name string) (res *C.char) {
+	env, unlock, err := loadEnv(envId)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, err)
+	}
+	defer unlock()
+
+	plan, err := env.App.Keepers.UpgradeKeeper.GetUpgradePlan(env.Ctx)
+	if err != nil {
+		return encodeErrToResultBytes(result.ExecuteError, errors.Wrap(err, "get upgrade plan"))
+	}
+	if plan.Name != name {
+		return encodeErrToResultBytes(result.ExecuteError, fmt.Errorf("the scheduled upgrade is %s, not %s", plan.Name, name))
+	}
+	// Without a handler the upgrade module halts the chain by panicking
+	if !env.App.Keepers.UpgradeKeeper.HasHandler(name) {
+		return encodeErrToResultBytes(result.ExecuteError, fmt.Errorf("no handler for the %s upgrade in this archway build", name))
+	}
+
+	// Blocks are committed even if a later one fails, a panicking handler leaves the env mid block
+	defer func() {
+		if r := recover(); r != nil {
+			res = encodeErrToResultBytes(result.ExecuteError, fmt.Errorf("upgrade %s panicked: %v", name, r))
+		}
+		storeEnv(envId, env)
+	}()
+	for env.Ctx.BlockHeight() < plan.Height {
+		if err := emptyBlock(&env); err != nil {
+			return encodeErrToResultBytes(result.ExecuteError, errors.Wrapf(err, "block %d", env.Ctx.BlockHeight()))
+		}
+	}
+
+	return encodeJsonResultBytes(result.ExecuteError, plan.Height)
+}
+
+// UpgradeNames returns the names of the upgrades the app has handlers for as json, oldest first
+//
+//export UpgradeNames
+func UpgradeNames() *C.char {
+	names := make([]string, 0, len(app.Upgrades))
+	for _, upgrade := range app.Upgrades {
+		names = append(names, upgrade.UpgradeName)
+	}
+
+	return encodeJsonResultBytes(result.QueryError, names)
+}
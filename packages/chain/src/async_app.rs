@@ -0,0 +1,248 @@
+use crate::module::type_url;
+use crate::{encode_msgs, to_proto, ArchwayApp, SimulationResult};
+use archway_proto::cosmwasm::wasm::v1::{
+    MsgExecuteContract, MsgExecuteContractResponse, QuerySmartContractStateRequest,
+    QuerySmartContractStateResponse,
+};
+use cosmrs::Any;
+use cosmwasm_std::Coin;
+use prost::Name;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::Any as PanicPayload;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use test_tube::{
+    Account, DecodeError, EncodeError, Runner, RunnerError, RunnerExecuteResult, RunnerResult,
+    SigningAccount,
+};
+use tokio::sync::oneshot;
+
+type Call = Box<dyn FnOnce(&mut ArchwayApp) + Send>;
+
+/// [`ArchwayApp`] running on a thread of its own, so async code can await its calls instead of
+/// blocking the runtime on them. Clones share the app, their calls run one at a time in the
+/// order they were made. The thread stops once every clone is dropped.
+///
+/// Signers are shared with the app thread, hence the [`Arc`] around them.
+#[derive(Clone)]
+pub struct AsyncArchwayApp {
+    calls: mpsc::Sender<Call>,
+}
+
+impl AsyncArchwayApp {
+    pub async fn new() -> RunnerResult<Self> {
+        Self::spawn(ArchwayApp::try_new).await
+    }
+
+    pub async fn new_with_chain_id(chain_id: &str) -> RunnerResult<Self> {
+        let chain_id = chain_id.to_string();
+        Self::spawn(move || ArchwayApp::try_new_with_chain_id(&chain_id)).await
+    }
+
+    /// Moves an app configured beforehand to its own thread
+    pub fn from_app(app: ArchwayApp) -> Self {
+        let (calls, receiver) = mpsc::channel::<Call>();
+        thread::Builder::new()
+            .name("archway-app".to_string())
+            .spawn(move || serve(app, receiver))
+            .expect("failed to spawn the app thread");
+
+        Self { calls }
+    }
+
+    async fn spawn<F>(init: F) -> RunnerResult<Self>
+    where
+        F: FnOnce() -> RunnerResult<ArchwayApp> + Send + 'static,
+    {
+        let (calls, receiver) = mpsc::channel::<Call>();
+        let (ready, initialized) = oneshot::channel();
+        thread::Builder::new()
+            .name("archway-app".to_string())
+            .spawn(move || match init() {
+                Ok(app) => {
+                    let _ = ready.send(Ok(()));
+                    serve(app, receiver);
+                }
+                Err(err) => {
+                    let _ = ready.send(Err(err));
+                }
+            })
+            .expect("failed to spawn the app thread");
+
+        initialized
+            .await
+            .expect("the app thread stopped while creating the app")?;
+
+        Ok(Self { calls })
+    }
+
+    /// Runs the closure on the app thread, a panic in it is resumed here
+    pub async fn run<F, T>(&self, call: F) -> T
+    where
+        F: FnOnce(&mut ArchwayApp) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel::<Result<T, Box<dyn PanicPayload + Send>>>();
+        self.calls
+            .send(Box::new(move |app: &mut ArchwayApp| {
+                let _ = sender.send(catch_unwind(AssertUnwindSafe(|| call(app))));
+            }))
+            .expect("the app thread stopped");
+
+        match receiver.await.expect("the app thread stopped") {
+            Ok(res) => res,
+            Err(panic) => resume_unwind(panic),
+        }
+    }
+
+    pub async fn init_account(&self, coins: &[Coin]) -> RunnerResult<Arc<SigningAccount>> {
+        let coins = coins.to_vec();
+        self.run(move |app| app.init_account(&coins).map(Arc::new))
+            .await
+    }
+
+    pub async fn init_accounts(
+        &self,
+        coins: &[Coin],
+        count: u64,
+    ) -> RunnerResult<Vec<Arc<SigningAccount>>> {
+        let coins = coins.to_vec();
+        self.run(move |app| {
+            app.init_accounts(&coins, count)
+                .map(|accounts| accounts.into_iter().map(Arc::new).collect())
+        })
+        .await
+    }
+
+    pub async fn execute<M, R>(
+        &self,
+        msg: M,
+        type_url: &str,
+        signer: &Arc<SigningAccount>,
+    ) -> RunnerExecuteResult<R>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default + Send + 'static,
+    {
+        self.execute_multiple(&[(msg, type_url)], signer).await
+    }
+
+    pub async fn execute_multiple<M, R>(
+        &self,
+        msgs: &[(M, &str)],
+        signer: &Arc<SigningAccount>,
+    ) -> RunnerExecuteResult<R>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default + Send + 'static,
+    {
+        self.execute_multiple_raw(encode_msgs(msgs)?, signer).await
+    }
+
+    pub async fn execute_multiple_raw<R>(
+        &self,
+        msgs: Vec<Any>,
+        signer: &Arc<SigningAccount>,
+    ) -> RunnerExecuteResult<R>
+    where
+        R: ::prost::Message + Default + Send + 'static,
+    {
+        let signer = signer.clone();
+        self.run(move |app| app.execute_multiple_raw(msgs, &signer))
+            .await
+    }
+
+    pub async fn query<Q, R>(&self, path: &str, query: &Q) -> RunnerResult<R>
+    where
+        Q: ::prost::Message + Clone + Send + 'static,
+        R: ::prost::Message + Default + Send + 'static,
+    {
+        let path = path.to_string();
+        let query = query.clone();
+        self.run(move |app| app.query(&path, &query)).await
+    }
+
+    /// Dry-runs the messages like [`ArchwayApp::simulate`]
+    pub async fn simulate<M, R>(
+        &self,
+        msgs: &[(M, &str)],
+        signer: &Arc<SigningAccount>,
+    ) -> RunnerResult<SimulationResult<R>>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default + Send + 'static,
+    {
+        let msgs = encode_msgs(msgs)?;
+        let signer = signer.clone();
+        self.run(move |app| app.simulate_raw(msgs, &signer)).await
+    }
+
+    pub async fn wasm_execute<M>(
+        &self,
+        contract: &str,
+        msg: &M,
+        funds: &[Coin],
+        signer: &Arc<SigningAccount>,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse>
+    where
+        M: ?Sized + Serialize,
+    {
+        let msg = MsgExecuteContract {
+            sender: signer.address(),
+            msg: serde_json::to_vec(msg).map_err(EncodeError::JsonEncodeError)?,
+            funds: funds.iter().map(to_proto).collect(),
+            contract: contract.to_owned(),
+        };
+
+        self.execute(msg, &type_url(&MsgExecuteContract::full_name()), signer)
+            .await
+    }
+
+    pub async fn wasm_query<M, Res>(&self, contract: &str, msg: &M) -> RunnerResult<Res>
+    where
+        M: ?Sized + Serialize,
+        Res: DeserializeOwned,
+    {
+        let res = self
+            .query::<QuerySmartContractStateRequest, QuerySmartContractStateResponse>(
+                "/cosmwasm.wasm.v1.Query/SmartContractState",
+                &QuerySmartContractStateRequest {
+                    address: contract.to_owned(),
+                    query_data: serde_json::to_vec(msg).map_err(EncodeError::JsonEncodeError)?,
+                },
+            )
+            .await?;
+
+        serde_json::from_slice(&res.data)
+            .map_err(DecodeError::JsonDecodeError)
+            .map_err(RunnerError::DecodeError)
+    }
+
+    pub async fn get_block_height(&self) -> RunnerResult<i64> {
+        self.run(|app| app.get_block_height()).await
+    }
+
+    pub async fn get_block_time_seconds(&self) -> RunnerResult<i64> {
+        self.run(|app| app.get_block_time_seconds()).await
+    }
+
+    pub async fn increase_time(&self, seconds: u64) -> RunnerResult<()> {
+        self.run(move |app| app.increase_time(seconds)).await
+    }
+
+    pub async fn skip_block(&self) -> RunnerResult<()> {
+        self.run(|app| app.skip_block()).await
+    }
+
+    pub async fn skip_blocks(&self, blocks: u64) -> RunnerResult<()> {
+        self.run(move |app| app.skip_blocks(blocks)).await
+    }
+}
+
+fn serve(mut app: ArchwayApp, calls: mpsc::Receiver<Call>) {
+    for call in calls {
+        call(&mut app);
+    }
+}
@@ -1,5 +1,68 @@
-use test_tube::bindings::GoUint64;
+use std::os::raw::c_char;
+use test_tube::bindings::{GoInt32, GoString, GoUint64};
 
 extern "C" {
-    pub fn SkipBlock(envId: GoUint64);
+    pub fn SetLogSink(sink: GoString, level: GoString) -> *mut c_char;
+    pub fn InitTestEnv() -> *mut c_char;
+    pub fn InitTestEnvWithChainId(chainId: GoString) -> *mut c_char;
+    pub fn CleanUp(envId: GoUint64) -> *mut c_char;
+    pub fn SkipBlock(envId: GoUint64) -> *mut c_char;
+    pub fn IncreaseTime(envId: GoUint64, seconds: GoUint64) -> *mut c_char;
+    pub fn GetBlockTime(envId: GoUint64) -> *mut c_char;
+    pub fn GetBlockHeight(envId: GoUint64) -> *mut c_char;
+    pub fn AccountSequence(envId: GoUint64, bech32Address: GoString) -> *mut c_char;
+    pub fn AccountNumber(envId: GoUint64, bech32Address: GoString) -> *mut c_char;
+    pub fn GetValidatorAddress(envId: GoUint64, n: GoInt32) -> *mut c_char;
+    pub fn GetValidatorPrivateKey(envId: GoUint64, n: GoInt32) -> *mut c_char;
+    pub fn SetGasTracing(envId: GoUint64, enabled: bool) -> *mut c_char;
+    pub fn SetStorageTracing(envId: GoUint64, enabled: bool) -> *mut c_char;
+    pub fn SetLogCapture(envId: GoUint64, level: GoString) -> *mut c_char;
+    pub fn TakeLogs(envId: GoUint64) -> *mut c_char;
+    pub fn SetContractDebugCapture(envId: GoUint64, enabled: bool) -> *mut c_char;
+    pub fn ApplyUpgrade(envId: GoUint64, name: GoString) -> *mut c_char;
+    pub fn UpgradeNames() -> *mut c_char;
+    pub fn InitAccount(envId: GoUint64, coinsJson: GoString) -> *mut c_char;
+    pub fn InitAccountFromSeed(envId: GoUint64, seed: GoString, coinsJson: GoString)
+        -> *mut c_char;
+    pub fn InitAccountFromMnemonic(
+        envId: GoUint64,
+        mnemonic: GoString,
+        hdPath: GoString,
+        coinsJson: GoString,
+    ) -> *mut c_char;
+    pub fn MintTo(envId: GoUint64, bech32Address: GoString, coinsJson: GoString) -> *mut c_char;
+    pub fn FundAccount(
+        envId: GoUint64,
+        bech32Address: GoString,
+        coinsJson: GoString,
+    ) -> *mut c_char;
+    pub fn FundModuleAccount(
+        envId: GoUint64,
+        moduleName: GoString,
+        coinsJson: GoString,
+    ) -> *mut c_char;
+    pub fn ExecuteAs(
+        envId: GoUint64,
+        bech32Address: GoString,
+        base64TxBodyBytes: GoString,
+    ) -> *mut c_char;
+    pub fn FinalizeAndCommit(envId: GoUint64, tx: GoString) -> *mut c_char;
+    pub fn SimulateTx(envId: GoUint64, base64TxBytes: GoString) -> *mut c_char;
+    pub fn QuerySmartWithGas(
+        envId: GoUint64,
+        bech32Contract: GoString,
+        base64QueryMsgBytes: GoString,
+        gasLimit: GoUint64,
+    ) -> *mut c_char;
+    pub fn IbcCreatePath(envIdA: GoUint64, envIdB: GoUint64) -> *mut c_char;
+    pub fn IbcRelay(envIdA: GoUint64, envIdB: GoUint64, pathJson: GoString) -> *mut c_char;
+    pub fn IbcOpenChannel(
+        envIdA: GoUint64,
+        envIdB: GoUint64,
+        pathJson: GoString,
+        portA: GoString,
+        portB: GoString,
+        version: GoString,
+        order: GoString,
+    ) -> *mut c_char;
 }
@@ -1,5 +1,7 @@
-use archway_proto::cosmos::base::v1beta1::Coin as ProtoCoin;
-use cosmwasm_std::{Coin, Uint128};
+use archway_proto::cosmos::base::v1beta1::{Coin as ProtoCoin, DecCoin as ProtoDecCoin};
+use cosmwasm_std::{Coin, DecCoin, Decimal256, Uint128, Uint256};
+use std::str::FromStr;
+use test_tube::{RunnerError, RunnerResult};
 
 pub fn from_legacy(coin: &cosmwasm_std_legacy::Coin) -> Coin {
     Coin {
@@ -14,3 +16,62 @@ pub fn to_proto(coin: &Coin) -> ProtoCoin {
         amount: coin.amount.to_string(),
     }
 }
+
+pub fn from_proto(coin: &ProtoCoin) -> Coin {
+    Coin {
+        denom: coin.denom.clone(),
+        amount: coin.amount.parse::<u128>().unwrap().into(),
+    }
+}
+
+/// Like [`from_proto`], returning an error instead of panicking on an invalid amount
+pub fn try_from_proto(coin: &ProtoCoin) -> RunnerResult<Coin> {
+    Ok(Coin {
+        denom: coin.denom.clone(),
+        amount: coin
+            .amount
+            .parse::<u128>()
+            .map_err(|_| invalid_coin(&coin.amount))?
+            .into(),
+    })
+}
+
+pub fn from_proto_dec(coin: &ProtoDecCoin) -> RunnerResult<DecCoin> {
+    // Protobuf encodes decimals as their atomics with 18 decimal places
+    let amount = if coin.amount.contains('.') {
+        Decimal256::from_str(&coin.amount).map_err(|_| invalid_coin(&coin.amount))?
+    } else {
+        let atomics = Uint256::from_str(&coin.amount).map_err(|_| invalid_coin(&coin.amount))?;
+        Decimal256::from_atomics(atomics, 18).map_err(|_| invalid_coin(&coin.amount))?
+    };
+
+    Ok(DecCoin::new(amount, coin.denom.clone()))
+}
+
+/// Parses coins printed by the chain, such as the `1000aarch,5uatom` fee of the tx events
+pub fn from_coins_str(coins: &str) -> RunnerResult<Vec<Coin>> {
+    coins
+        .split(',')
+        .filter(|coin| !coin.is_empty())
+        .map(|coin| {
+            let denom_start = coin
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(|| invalid_coin(coin))?;
+            let (amount, denom) = coin.split_at(denom_start);
+
+            Ok(Coin {
+                denom: denom.to_string(),
+                amount: amount
+                    .parse::<u128>()
+                    .map_err(|_| invalid_coin(coin))?
+                    .into(),
+            })
+        })
+        .collect()
+}
+
+fn invalid_coin(coin: impl std::fmt::Display) -> RunnerError {
+    RunnerError::QueryError {
+        msg: format!("invalid coin {}", coin),
+    }
+}
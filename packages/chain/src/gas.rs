@@ -0,0 +1,71 @@
+use crate::json_event_attribute;
+use cosmwasm_std::Event;
+use serde::{Deserialize, Serialize};
+use test_tube::RunnerResult;
+
+/// Event attached to tx results once gas tracing is enabled with [`crate::ArchwayApp::set_gas_tracing`]
+pub const GAS_BREAKDOWN_EVENT: &str = "litmus_gas_breakdown";
+const BREAKDOWN_ATTRIBUTE: &str = "breakdown";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnteGas {
+    pub total: u64,
+    /// Derived from the tx size cost of the auth params
+    pub tx_size: u64,
+    /// Derived from the secp256k1 verification cost of the auth params
+    pub signature_verification: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmGas {
+    /// Charged to load the contract before calling it
+    pub setup: u64,
+    pub compile: u64,
+    /// Reported by the VM for running the contract
+    pub compute: u64,
+    /// Charged for the events and attributes emitted by the contract
+    pub events: u64,
+}
+
+/// KV store accesses of the executed messages, byte counts assume the default KV gas config
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageGas {
+    pub reads: u64,
+    pub read_bytes: u64,
+    pub read_gas: u64,
+    pub writes: u64,
+    pub write_bytes: u64,
+    pub write_gas: u64,
+    pub deletes: u64,
+    pub delete_gas: u64,
+    pub has: u64,
+    pub has_gas: u64,
+    pub iterations: u64,
+    pub iteration_bytes: u64,
+    pub iteration_gas: u64,
+}
+
+impl StorageGas {
+    pub fn total(&self) -> u64 {
+        self.read_gas + self.write_gas + self.delete_gas + self.has_gas + self.iteration_gas
+    }
+}
+
+/// Gas used by a tx split by where it was spent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasBreakdown {
+    pub total: u64,
+    pub ante: AnteGas,
+    pub wasm: WasmGas,
+    pub storage: StorageGas,
+    pub refunded: u64,
+    /// Gas consumed outside of the other categories, e.g. by message handlers of the SDK
+    pub other: u64,
+}
+
+impl GasBreakdown {
+    /// Reads the breakdown from the events of an execution, it is only there when gas tracing is enabled
+    pub fn from_events(events: &[Event]) -> RunnerResult<Option<Self>> {
+        json_event_attribute(events, GAS_BREAKDOWN_EVENT, BREAKDOWN_ATTRIBUTE)
+    }
+}
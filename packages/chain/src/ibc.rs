@@ -0,0 +1,252 @@
+use crate::bindings::{IbcCreatePath, IbcOpenChannel, IbcRelay};
+use crate::module::{DenomTrace, Module, Transfer, TRANSFER_PORT, TRANSFER_VERSION};
+use crate::ArchwayApp;
+use cosmwasm_std::Coin;
+use serde::{Deserialize, Serialize};
+use test_tube::runner::error::DecodeError;
+use test_tube::runner::result::RawResult;
+use test_tube::{redefine_as_go_string, EncodeError, RunnerError, RunnerResult, SigningAccount};
+
+/// Time the receiving chain has to get a transfer sent by the coordinator, in nanoseconds
+const TRANSFER_TIMEOUT: u64 = 10 * 60 * 1_000_000_000;
+
+/// Clients and connections linking two chains, the `a` side belongs to the first chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IbcPath {
+    pub client_a: String,
+    pub client_b: String,
+    pub connection_a: String,
+    pub connection_b: String,
+}
+
+/// Both ends of a channel opened on an [`IbcPath`]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct IbcChannel {
+    pub port_a: String,
+    pub channel_a: String,
+    pub port_b: String,
+    pub channel_b: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelOrder {
+    Unordered,
+    Ordered,
+}
+
+impl ChannelOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChannelOrder::Unordered => "ORDER_UNORDERED",
+            ChannelOrder::Ordered => "ORDER_ORDERED",
+        }
+    }
+}
+
+/// Amount of messages delivered by a [`Relayer::relay`] call
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct RelayReport {
+    /// Channel handshake steps, a channel takes three of them to open
+    pub handshakes: u64,
+    pub packets: u64,
+    pub acks: u64,
+    pub timeouts: u64,
+}
+
+/// Relayer stand-in linking two in-process chains.
+/// Every relayed message is delivered in its own block without going through the ante handler,
+/// so relaying does not spend any account funds
+pub struct Relayer<'a> {
+    chain_a: &'a ArchwayApp,
+    chain_b: &'a ArchwayApp,
+    path: IbcPath,
+}
+
+impl<'a> Relayer<'a> {
+    /// Creates a light client of each chain on the other one and opens a connection between them
+    pub fn new(chain_a: &'a ArchwayApp, chain_b: &'a ArchwayApp) -> RunnerResult<Self> {
+        let path = unsafe {
+            let res = IbcCreatePath(chain_a.id, chain_b.id);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+
+        Ok(Self {
+            chain_a,
+            chain_b,
+            path: serde_json::from_slice(&path).map_err(DecodeError::JsonDecodeError)?,
+        })
+    }
+
+    pub fn chain_a(&self) -> &'a ArchwayApp {
+        self.chain_a
+    }
+
+    pub fn chain_b(&self) -> &'a ArchwayApp {
+        self.chain_b
+    }
+
+    pub fn path(&self) -> &IbcPath {
+        &self.path
+    }
+
+    /// Completes the channel handshakes and delivers the packets, acks and timeouts pending
+    /// in both directions, until there is nothing left to relay.
+    /// Packets are timed out once the receiving chain reaches their timeout, so a timeout can be
+    /// forced by increasing the time of the receiving chain before relaying
+    pub fn relay(&self) -> RunnerResult<RelayReport> {
+        let path = serde_json::to_string(&self.path).map_err(EncodeError::JsonEncodeError)?;
+        redefine_as_go_string!(path);
+
+        let report = unsafe {
+            let res = IbcRelay(self.chain_a.id, self.chain_b.id, path);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+
+        serde_json::from_slice(&report)
+            .map_err(DecodeError::JsonDecodeError)
+            .map_err(RunnerError::DecodeError)
+    }
+}
+
+/// Links two chains through a connection and opens channels over it
+pub struct IbcCoordinator<'a> {
+    relayer: Relayer<'a>,
+}
+
+impl<'a> IbcCoordinator<'a> {
+    pub fn new(chain_a: &'a ArchwayApp, chain_b: &'a ArchwayApp) -> RunnerResult<Self> {
+        Ok(Self {
+            relayer: Relayer::new(chain_a, chain_b)?,
+        })
+    }
+
+    pub fn relayer(&self) -> &Relayer<'a> {
+        &self.relayer
+    }
+
+    pub fn path(&self) -> &IbcPath {
+        self.relayer.path()
+    }
+
+    /// Opens a channel initiated by chain a, relaying its whole handshake
+    pub fn create_channel(
+        &self,
+        port_a: &str,
+        port_b: &str,
+        version: &str,
+        order: ChannelOrder,
+    ) -> RunnerResult<IbcChannel> {
+        let path =
+            serde_json::to_string(self.relayer.path()).map_err(EncodeError::JsonEncodeError)?;
+        let order = order.as_str();
+        redefine_as_go_string!(path);
+        redefine_as_go_string!(port_a);
+        redefine_as_go_string!(port_b);
+        redefine_as_go_string!(version);
+        redefine_as_go_string!(order);
+
+        let channel = unsafe {
+            let res = IbcOpenChannel(
+                self.relayer.chain_a.id,
+                self.relayer.chain_b.id,
+                path,
+                port_a,
+                port_b,
+                version,
+                order,
+            );
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+
+        serde_json::from_slice(&channel)
+            .map_err(DecodeError::JsonDecodeError)
+            .map_err(RunnerError::DecodeError)
+    }
+
+    /// Opens an ICS-20 channel between the transfer modules of both chains
+    pub fn create_transfer_channel(&self) -> RunnerResult<IbcChannel> {
+        self.create_channel(
+            TRANSFER_PORT,
+            TRANSFER_PORT,
+            TRANSFER_VERSION,
+            ChannelOrder::Unordered,
+        )
+    }
+
+    /// Delivers every pending packet and acknowledgement between the chains
+    pub fn relay_all(&self) -> RunnerResult<RelayReport> {
+        self.relayer.relay()
+    }
+
+    /// Transfers the token from chain a to chain b and relays the packet and its ack,
+    /// returning the denom trace of the token on chain b
+    pub fn transfer_a_to_b(
+        &self,
+        channel: &IbcChannel,
+        token: &Coin,
+        sender: &SigningAccount,
+        receiver: &str,
+    ) -> RunnerResult<DenomTrace> {
+        let trace = self.transfer(
+            self.relayer.chain_a,
+            self.relayer.chain_b,
+            &channel.channel_a,
+            token,
+            sender,
+            receiver,
+        )?;
+
+        Ok(trace.received(
+            &channel.port_a,
+            &channel.channel_a,
+            &channel.port_b,
+            &channel.channel_b,
+        ))
+    }
+
+    /// Transfers the token from chain b to chain a and relays the packet and its ack,
+    /// returning the denom trace of the token on chain a
+    pub fn transfer_b_to_a(
+        &self,
+        channel: &IbcChannel,
+        token: &Coin,
+        sender: &SigningAccount,
+        receiver: &str,
+    ) -> RunnerResult<DenomTrace> {
+        let trace = self.transfer(
+            self.relayer.chain_b,
+            self.relayer.chain_a,
+            &channel.channel_b,
+            token,
+            sender,
+            receiver,
+        )?;
+
+        Ok(trace.received(
+            &channel.port_b,
+            &channel.channel_b,
+            &channel.port_a,
+            &channel.channel_a,
+        ))
+    }
+
+    /// Sends the transfer and relays it, returning the denom trace on the sending chain
+    fn transfer(
+        &self,
+        from: &ArchwayApp,
+        to: &ArchwayApp,
+        channel: &str,
+        token: &Coin,
+        sender: &SigningAccount,
+        receiver: &str,
+    ) -> RunnerResult<DenomTrace> {
+        let transfer = Transfer::new(from);
+        let trace = transfer.denom_trace(&token.denom)?;
+
+        let timeout = to.get_block_time_nanos()? as u64 + TRANSFER_TIMEOUT;
+        transfer.transfer(channel, token, receiver, timeout, sender)?;
+        self.relay_all()?;
+
+        Ok(trace)
+    }
+}
@@ -1,30 +1,58 @@
+#[cfg(feature = "async")]
+pub mod async_app;
 mod bindings;
 mod coin_compat;
+pub mod gas;
+pub mod ibc;
+pub mod log;
 pub mod module;
+pub mod state;
+pub mod storage;
 
 pub use archway_proto;
-use cosmrs::proto::tendermint::abci::ResponseFinalizeBlock;
+#[cfg(feature = "async")]
+pub use async_app::AsyncArchwayApp;
+use cosmrs::proto::tendermint::abci::{ExecTxResult, ResponseFinalizeBlock};
 pub use cosmwasm_std;
-use std::ffi::CString;
+use std::ffi::c_char;
 use std::str::FromStr;
-
-use crate::bindings::SkipBlock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::bindings::{
+    AccountNumber, AccountSequence, ApplyUpgrade, ExecuteAs, FinalizeAndCommit, FundAccount,
+    FundModuleAccount, GetBlockHeight, GetBlockTime, GetValidatorPrivateKey, IncreaseTime,
+    InitAccount, InitAccountFromMnemonic, InitAccountFromSeed, InitTestEnv, InitTestEnvWithChainId,
+    MintTo, QuerySmartWithGas, SetContractDebugCapture, SetGasTracing, SetLogCapture,
+    SetStorageTracing, SimulateTx, SkipBlock, TakeLogs, UpgradeNames,
+};
+use crate::log::{LogEntry, LogLevel};
+use crate::module::{CwFees, GovWithAppAccess, Module, Rewards, Upgrade, Wasm};
+use crate::state::ContractState;
+use archway_proto::archway::cwfees::v1::IsGrantingContractRequest;
+use archway_proto::archway::rewards::v1::{
+    QueryEstimateTxFeesRequest, QueryMinConsensusFeeRequest,
+};
+use archway_proto::cosmos::base::query::v1beta1::PageRequest;
+use archway_proto::cosmos::upgrade::v1beta1::{MsgSoftwareUpgrade, Plan, QueryCurrentPlanRequest};
+use archway_proto::cosmwasm::wasm::v1::MsgExecuteContract;
+use archway_proto::tendermint::google::protobuf::Any as ProtoAny;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine;
 pub use coin_compat::*;
+use cosmrs::proto::cosmos::tx::v1beta1::{TxBody, TxRaw};
 use cosmrs::Any;
-use cosmwasm_std::Coin;
-use prost::Message;
+use cosmwasm_std::{Coin, DecCoin, Decimal256, Event, Uint128};
+use prost::{Message, Name};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 pub use test_tube;
-use test_tube::bindings::{
-    AccountNumber, AccountSequence, Commit, FinalizeBlock, GetBlockHeight, GetBlockTime,
-    GetValidatorPrivateKey, IncreaseTime, InitAccount, InitTestEnv, Query, Simulate,
-};
+use test_tube::bindings::{Query, Simulate};
 use test_tube::cosmrs::crypto::secp256k1::SigningKey;
 use test_tube::cosmrs::tx::{Fee, SignerInfo};
 use test_tube::cosmrs::{tx, AccountId};
 use test_tube::runner::error::DecodeError;
-use test_tube::runner::result::RawResult;
+use test_tube::runner::result::{ExecuteResponse, RawResult};
 use test_tube::{
     cosmrs, redefine_as_go_string, Account, EncodeError, FeeSetting, Runner, RunnerError,
     RunnerExecuteResult, RunnerResult, SigningAccount,
@@ -36,8 +64,14 @@ pub const ADDRESS_PREFIX: &str = "cosmos";
 pub const CHAIN_ID: &str = "archway-1";
 
 pub const DEFAULT_GAS_ADJUSTMENT: f64 = 1.4;
-// pub const GAS_PRICE: u128 = 900_000_000_000;
-const GAS_PRICE: u128 = 140_000_000_000;
+// pub const DEFAULT_GAS_PRICE: u128 = 900_000_000_000;
+pub const DEFAULT_GAS_PRICE: u128 = 140_000_000_000;
+
+/// Default `smart_query_gas_limit` of wasmd, which bounds the queries a contract makes to another
+pub const DEFAULT_SMART_QUERY_GAS_LIMIT: u64 = 3_000_000;
+
+/// Derivation path of the first account of the cosmos coin type
+pub const DEFAULT_HD_PATH: &str = "m/44'/118'/0'/0/0";
 
 pub fn aarch(amount: u128) -> Coin {
     Coin::new(amount, FEE_DENOM)
@@ -47,105 +81,355 @@ pub fn arch(amount: u128) -> Coin {
     aarch(amount * 10u128.pow(18))
 }
 
+/// How the gas price of automatically calculated fees is picked. Whatever the strategy, the fee
+/// is raised to the minimum the chain accepts when it is lower, see
+/// [`ArchwayApp::fee_for_gas_used`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum GasPriceStrategy {
+    /// Uses the gas price of the signer's fee setting, which is the app's default gas price
+    /// for accounts created by it
+    Fixed,
+    /// Queries the minimum consensus fee from the rewards module before every tx, so the fee
+    /// is exactly the minimum even when the signer's gas price is higher
+    Queried,
+    /// Uses the given gas price for every signer
+    Custom(DecCoin),
+}
+
+/// Execution whose fee was paid by a granting contract
+#[derive(Debug, Clone, PartialEq)]
+pub struct SponsoredExecuteResponse<R>
+where
+    R: ::prost::Message + Default,
+{
+    pub response: ExecuteResponse<R>,
+    /// Amount of the fee denom spent by the granting contract
+    pub granter_paid: u128,
+    /// Amount of the fee denom spent by the signer
+    pub signer_paid: u128,
+}
+
+/// Dry-run of a tx, nothing it did is committed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationResult<R>
+where
+    R: ::prost::Message + Default,
+{
+    /// The gas wanted is the gas limit the fee setting would request
+    pub response: ExecuteResponse<R>,
+    /// Fee the tx would pay with the fee setting
+    pub fee: Fee,
+}
+
+/// Smart query response along with the gas it consumed
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeteredQueryResponse<R> {
+    pub data: R,
+    pub gas_used: u64,
+    /// Zero when the query was unbounded
+    pub gas_limit: u64,
+}
+
+/// Address acting as a signer without its key, see [`ArchwayApp::impersonate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpersonatedSigner {
+    address: String,
+}
+
+impl ImpersonatedSigner {
+    pub fn address(&self) -> String {
+        self.address.clone()
+    }
+}
+
+/// Archway chain running in process through libarchway.
+///
+/// # Thread model
+/// The app is `Send + Sync`, it can be moved to another thread or shared between threads.
+/// libarchway locks the env for each FFI call, so each call runs alone on the app's state and
+/// separate apps run in parallel. A tx is finalized and committed within one such call, but the
+/// methods making several calls are not atomic: executing a tx also queries the signer's account
+/// and may simulate it first, and other threads can change the state in between. Txs sent
+/// concurrently by the same signer race for its account sequence, concurrent executions should
+/// use distinct signers, and reads racing with writes see the state before or after them.
 pub struct ArchwayApp {
     id: u64,
     fee_denom: String,
     chain_id: String,
     address_prefix: String,
     default_gas_adjustment: f64,
+    default_gas_price: u128,
+    gas_price_strategy: GasPriceStrategy,
+    account_seed: Option<String>,
+    seeded_accounts: AtomicU64,
+    created_addresses: AtomicU64,
 }
 
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ArchwayApp>();
+};
+
 impl ArchwayApp {
     pub fn new() -> Self {
-        let id = unsafe { InitTestEnv() };
+        Self::try_new().expect("failed to init the test env")
+    }
+
+    pub fn try_new() -> RunnerResult<Self> {
+        let id = unsafe { decode_json_result(InitTestEnv())? };
+        Ok(Self::from_env(id, CHAIN_ID))
+    }
+
+    /// Creates an app with a different chain id, used to stand in as an IBC counterparty.
+    /// The chain id must end with its revision number, e.g. `archway-2`
+    pub fn new_with_chain_id(chain_id: &str) -> Self {
+        Self::try_new_with_chain_id(chain_id).expect("failed to init the test env")
+    }
+
+    pub fn try_new_with_chain_id(chain_id: &str) -> RunnerResult<Self> {
+        let id = {
+            redefine_as_go_string!(chain_id);
+            unsafe { decode_json_result(InitTestEnvWithChainId(chain_id))? }
+        };
+        Ok(Self::from_env(id, chain_id))
+    }
+
+    fn from_env(id: u64, chain_id: &str) -> Self {
         Self {
             id,
             fee_denom: FEE_DENOM.to_string(),
-            chain_id: CHAIN_ID.to_string(),
+            chain_id: chain_id.to_string(),
             address_prefix: ADDRESS_PREFIX.to_string(),
             default_gas_adjustment: DEFAULT_GAS_ADJUSTMENT,
+            default_gas_price: DEFAULT_GAS_PRICE,
+            gas_price_strategy: GasPriceStrategy::Fixed,
+            account_seed: None,
+            seeded_accounts: AtomicU64::new(0),
+            created_addresses: AtomicU64::new(0),
         }
     }
 }
 
 impl ArchwayApp {
-    pub fn get_block_time_nanos(&self) -> i64 {
-        unsafe { GetBlockTime(self.id) }
+    pub fn chain_id(&self) -> &str {
+        &self.chain_id
     }
 
-    /// Get the current block time in seconds
-    pub fn get_block_time_seconds(&self) -> i64 {
-        self.get_block_time_nanos() / 1_000_000_000i64
+    pub fn fee_denom(&self) -> &str {
+        &self.fee_denom
     }
 
-    pub fn get_block_height(&self) -> i64 {
-        unsafe { GetBlockHeight(self.id) }
+    /// Sets the denom of the default gas price and of the fees paid on simulations
+    pub fn set_fee_denom(&mut self, denom: impl Into<String>) {
+        self.fee_denom = denom.into();
     }
 
-    pub fn increase_time(&self, seconds: u64) {
-        unsafe {
-            IncreaseTime(self.id, seconds);
+    pub fn default_gas_adjustment(&self) -> f64 {
+        self.default_gas_adjustment
+    }
+
+    /// Sets the gas adjustment of the accounts created afterwards
+    pub fn set_default_gas_adjustment(&mut self, gas_adjustment: f64) {
+        self.default_gas_adjustment = gas_adjustment;
+    }
+
+    pub fn default_gas_price(&self) -> Coin {
+        Coin::new(self.default_gas_price, self.fee_denom.clone())
+    }
+
+    /// Sets the gas price of the accounts created afterwards, in the fee denom
+    pub fn set_default_gas_price(&mut self, gas_price: u128) {
+        self.default_gas_price = gas_price;
+    }
+
+    /// Fee setting given to the accounts created by the app
+    pub fn default_fee_setting(&self) -> FeeSetting {
+        FeeSetting::Auto {
+            gas_price: self.default_gas_price(),
+            gas_adjustment: self.default_gas_adjustment,
         }
     }
 
-    /// Submits an empty block to the chain
-    pub fn skip_block(&self) {
+    /// Attaches a [`gas::GasBreakdown`] event to the result of every executed or simulated tx,
+    /// read it back with [`gas::GasBreakdown::from_events`]
+    pub fn set_gas_tracing(&mut self, enabled: bool) -> RunnerResult<()> {
+        unsafe { RawResult::from_non_null_ptr(SetGasTracing(self.id, enabled)).into_result()? };
+        Ok(())
+    }
+
+    /// Attaches the [`storage::StorageAccess`] list of every executed or simulated tx to its result,
+    /// read it back with [`storage::StorageAccess::from_events`]
+    pub fn set_storage_tracing(&mut self, enabled: bool) -> RunnerResult<()> {
+        unsafe { RawResult::from_non_null_ptr(SetStorageTracing(self.id, enabled)).into_result()? };
+        Ok(())
+    }
+
+    /// Keeps the chain log entries of the app from the given level on until they are taken,
+    /// `None` stops the capture. The sink set with [`log::set_log_sink`] still gets them
+    pub fn set_log_capture(&mut self, level: Option<LogLevel>) -> RunnerResult<()> {
+        let level = level.map_or("disabled", |level| level.as_str());
+        redefine_as_go_string!(level);
+
+        unsafe { RawResult::from_non_null_ptr(SetLogCapture(self.id, level)).into_result()? };
+        Ok(())
+    }
+
+    /// Log entries captured since the last call
+    pub fn take_logs(&self) -> RunnerResult<Vec<LogEntry>> {
+        unsafe { decode_json_result(TakeLogs(self.id)) }
+    }
+
+    /// Attaches the messages printed by the contracts with `deps.api.debug` to the result of every
+    /// executed or simulated tx, read them back with [`log::DebugMessage::from_events`].
+    /// The contracts print to stderr, which is redirected while a capturing app runs a block, so
+    /// those blocks run one at a time across the process. Only available on unix targets
+    pub fn set_contract_debug_capture(&mut self, enabled: bool) -> RunnerResult<()> {
         unsafe {
-            SkipBlock(self.id);
+            RawResult::from_non_null_ptr(SetContractDebugCapture(self.id, enabled)).into_result()?
+        };
+        Ok(())
+    }
+
+    pub fn account_seed(&self) -> Option<&str> {
+        self.account_seed.as_deref()
+    }
+
+    /// Makes `init_account` derive its keys from the seed and the amount of accounts created so far,
+    /// so the same sequence of calls always yields the same addresses
+    pub fn set_account_seed(&mut self, seed: Option<String>) {
+        self.account_seed = seed;
+        self.seeded_accounts.store(0, Ordering::SeqCst);
+    }
+
+    pub fn gas_price_strategy(&self) -> &GasPriceStrategy {
+        &self.gas_price_strategy
+    }
+
+    pub fn set_gas_price_strategy(&mut self, strategy: GasPriceStrategy) {
+        self.gas_price_strategy = strategy;
+    }
+
+    /// Queries the minimum gas price accepted by the chain, it follows inflation and block gas
+    pub fn min_consensus_fee(&self) -> RunnerResult<DecCoin> {
+        let res = Rewards::new(self).query_min_consensus_fee(&QueryMinConsensusFeeRequest {})?;
+        res.fee
+            .map(|fee| from_proto_dec(&fee))
+            .transpose()?
+            .ok_or_else(|| RunnerError::QueryError {
+                msg: "minimum consensus fee not set".to_string(),
+            })
+    }
+
+    /// Gas price used by the signer according to the app's [`GasPriceStrategy`]
+    pub fn gas_price(&self, gas_price: &Coin) -> RunnerResult<DecCoin> {
+        match &self.gas_price_strategy {
+            GasPriceStrategy::Fixed => Ok(DecCoin::new(
+                Decimal256::from_ratio(gas_price.amount, 1u128),
+                gas_price.denom.clone(),
+            )),
+            GasPriceStrategy::Queried => self.min_consensus_fee(),
+            GasPriceStrategy::Custom(price) => Ok(price.clone()),
         }
     }
 
+    pub fn get_block_time_nanos(&self) -> RunnerResult<i64> {
+        unsafe { decode_json_result(GetBlockTime(self.id)) }
+    }
+
+    /// Get the current block time in seconds
+    pub fn get_block_time_seconds(&self) -> RunnerResult<i64> {
+        Ok(self.get_block_time_nanos()? / 1_000_000_000i64)
+    }
+
+    pub fn get_block_height(&self) -> RunnerResult<i64> {
+        unsafe { decode_json_result(GetBlockHeight(self.id)) }
+    }
+
+    /// Submits an empty block the given seconds after the current one
+    pub fn increase_time(&self, seconds: u64) -> RunnerResult<()> {
+        unsafe { decode_json_result::<i64>(IncreaseTime(self.id, seconds))? };
+        Ok(())
+    }
+
+    /// Submits an empty block to the chain
+    pub fn skip_block(&self) -> RunnerResult<()> {
+        unsafe { RawResult::from_non_null_ptr(SkipBlock(self.id)).into_result()? };
+        Ok(())
+    }
+
     /// Submits multiple empty blocks to the chain
-    pub fn skip_blocks(&self, blocks: u64) {
+    pub fn skip_blocks(&self, blocks: u64) -> RunnerResult<()> {
         for _ in 0..blocks {
-            self.skip_block()
+            self.skip_block()?;
         }
+        Ok(())
     }
 
     pub fn get_first_validator_signing_account(&self) -> RunnerResult<SigningAccount> {
         let base64_priv = unsafe {
-            let val_priv = GetValidatorPrivateKey(self.id, 0);
-            CString::from_raw(val_priv)
+            let res = GetValidatorPrivateKey(self.id, 0);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+
+        self.signing_account(std::str::from_utf8(&base64_priv).map_err(DecodeError::Utf8Error)?)
+    }
+
+    pub fn init_account(&self, coins: &[Coin]) -> RunnerResult<SigningAccount> {
+        if let Some(seed) = &self.account_seed {
+            let index = self.seeded_accounts.fetch_add(1, Ordering::SeqCst);
+            return self.init_account_from_seed(&format!("{}/{}", seed, index), coins);
         }
-        .to_str()
-        .map_err(DecodeError::Utf8Error)?
-        .to_string();
 
-        let secp256k1_priv = BASE64_STANDARD
-            .decode(base64_priv)
-            .map_err(DecodeError::Base64DecodeError)?;
-        let signging_key = SigningKey::from_slice(&secp256k1_priv).map_err(|e| {
-            let msg = e.to_string();
-            DecodeError::SigningKeyDecodeError { msg }
-        })?;
+        let coins_json = coins_to_json(coins)?;
+        redefine_as_go_string!(coins_json);
 
-        Ok(SigningAccount::new(
-            self.address_prefix.clone(),
-            signging_key,
-            FeeSetting::Auto {
-                gas_price: Coin::new(GAS_PRICE, self.fee_denom.clone()),
-                gas_adjustment: self.default_gas_adjustment,
-            },
-        ))
+        let base64_priv = unsafe {
+            let res = InitAccount(self.id, coins_json);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+
+        self.signing_account(std::str::from_utf8(&base64_priv).map_err(DecodeError::Utf8Error)?)
     }
 
-    pub fn init_account(&self, coins: &[Coin]) -> RunnerResult<SigningAccount> {
-        let mut coins = coins.to_vec();
+    /// Creates an account whose key is derived from the seed, the same seed always yields the same address
+    pub fn init_account_from_seed(
+        &self,
+        seed: &str,
+        coins: &[Coin],
+    ) -> RunnerResult<SigningAccount> {
+        let coins_json = coins_to_json(coins)?;
+        redefine_as_go_string!(seed);
+        redefine_as_go_string!(coins_json);
+
+        let base64_priv = unsafe {
+            let res = InitAccountFromSeed(self.id, seed, coins_json);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
 
-        // invalid coins if denom are unsorted
-        coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+        self.signing_account(std::str::from_utf8(&base64_priv).map_err(DecodeError::Utf8Error)?)
+    }
 
-        let coins_json = serde_json::to_string(&coins).map_err(EncodeError::JsonEncodeError)?;
+    /// Creates the account of a BIP-39 mnemonic at the given derivation path, e.g. [`DEFAULT_HD_PATH`]
+    pub fn init_account_from_mnemonic(
+        &self,
+        mnemonic: &str,
+        hd_path: &str,
+        coins: &[Coin],
+    ) -> RunnerResult<SigningAccount> {
+        let coins_json = coins_to_json(coins)?;
+        redefine_as_go_string!(mnemonic);
+        redefine_as_go_string!(hd_path);
         redefine_as_go_string!(coins_json);
 
         let base64_priv = unsafe {
-            let addr = InitAccount(self.id, coins_json);
-            CString::from_raw(addr)
-        }
-        .to_str()
-        .map_err(DecodeError::Utf8Error)?
-        .to_string();
+            let res = InitAccountFromMnemonic(self.id, mnemonic, hd_path, coins_json);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+
+        self.signing_account(std::str::from_utf8(&base64_priv).map_err(DecodeError::Utf8Error)?)
+    }
 
+    fn signing_account(&self, base64_priv: &str) -> RunnerResult<SigningAccount> {
         let secp256k1_priv = BASE64_STANDARD
             .decode(base64_priv)
             .map_err(DecodeError::Base64DecodeError)?;
@@ -157,21 +441,123 @@ impl ArchwayApp {
         Ok(SigningAccount::new(
             self.address_prefix.clone(),
             signging_key,
-            FeeSetting::Auto {
-                gas_price: aarch(GAS_PRICE),
-                gas_adjustment: self.default_gas_adjustment,
-            },
+            self.default_fee_setting(),
         ))
     }
 
-    pub fn get_account_sequence(&self, address: &str) -> u64 {
+    /// Mints the coins to an existing address, registering the metadata of new denoms the same
+    /// way `init_account` does. The app has no token factory, so this is how custom denoms are created
+    pub fn mint_to(&self, address: &str, coins: &[Coin]) -> RunnerResult<()> {
+        let coins_json = coins_to_json(coins)?;
+        redefine_as_go_string!(address);
+        redefine_as_go_string!(coins_json);
+
+        unsafe {
+            let res = MintTo(self.id, address, coins_json);
+            RawResult::from_non_null_ptr(res).into_result()?;
+        }
+
+        Ok(())
+    }
+
+    /// Tops up an existing address, e.g. a contract or a validator, without sending a tx
+    pub fn fund(&self, address: &str, coins: &[Coin]) -> RunnerResult<()> {
+        let coins_json = coins_to_json(coins)?;
+        redefine_as_go_string!(address);
+        redefine_as_go_string!(coins_json);
+
+        unsafe {
+            let res = FundAccount(self.id, address, coins_json);
+            RawResult::from_non_null_ptr(res).into_result()?;
+        }
+
+        Ok(())
+    }
+
+    /// Tops up a module account by its module name, module accounts cannot be funded by address
+    /// since the bank module blocks sends to them
+    pub fn fund_module(&self, module_name: &str, coins: &[Coin]) -> RunnerResult<()> {
+        let coins_json = coins_to_json(coins)?;
+        redefine_as_go_string!(module_name);
+        redefine_as_go_string!(coins_json);
+
+        unsafe {
+            let res = FundModuleAccount(self.id, module_name, coins_json);
+            RawResult::from_non_null_ptr(res).into_result()?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates a funded address that has no key, it can only act through [`ArchwayApp::impersonate`]
+    pub fn init_address(&self, coins: &[Coin]) -> RunnerResult<String> {
+        let index = self.created_addresses.fetch_add(1, Ordering::SeqCst);
+        let hash = Sha256::digest(format!("{}/address/{}", self.chain_id, index).as_bytes());
+        let address = AccountId::new(&self.address_prefix, &hash[..20])?.to_string();
+
+        if !coins.is_empty() {
+            self.mint_to(&address, coins)?;
+        }
+
+        Ok(address)
+    }
+
+    /// Signs on behalf of any address, e.g. a module account or an address imported from a
+    /// state export. Its txs skip the ante handler, so they pay no fees
+    pub fn impersonate(&self, address: &str) -> ImpersonatedSigner {
+        ImpersonatedSigner {
+            address: address.to_string(),
+        }
+    }
+
+    /// Executes the messages as the impersonated signer, every message must be signed by it
+    pub fn execute_multiple_as<M, R>(
+        &self,
+        msgs: &[(M, &str)],
+        signer: &ImpersonatedSigner,
+    ) -> RunnerExecuteResult<R>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default,
+    {
+        self.execute_multiple_raw_as(encode_msgs(msgs)?, signer)
+    }
+
+    pub fn execute_multiple_raw_as<R>(
+        &self,
+        msgs: Vec<Any>,
+        signer: &ImpersonatedSigner,
+    ) -> RunnerExecuteResult<R>
+    where
+        R: ::prost::Message + Default,
+    {
+        let body = TxBody {
+            messages: msgs,
+            ..Default::default()
+        };
+        let base64_body = BASE64_STANDARD.encode(body.encode_to_vec());
+        let address = signer.address.as_str();
+        redefine_as_go_string!(address);
+        redefine_as_go_string!(base64_body);
+
+        let res = unsafe {
+            let res = ExecuteAs(self.id, address, base64_body);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+
+        ResponseFinalizeBlock::decode(res.as_slice())
+            .map_err(DecodeError::ProtoDecodeError)?
+            .try_into()
+    }
+
+    pub fn get_account_sequence(&self, address: &str) -> RunnerResult<u64> {
         redefine_as_go_string!(address);
-        unsafe { AccountSequence(self.id, address) }
+        unsafe { decode_json_result(AccountSequence(self.id, address)) }
     }
 
-    pub fn get_account_number(&self, address: &str) -> u64 {
+    pub fn get_account_number(&self, address: &str) -> RunnerResult<u64> {
         redefine_as_go_string!(address);
-        unsafe { AccountNumber(self.id, address) }
+        unsafe { decode_json_result(AccountNumber(self.id, address)) }
     }
 
     pub fn init_accounts(&self, coins: &[Coin], count: u64) -> RunnerResult<Vec<SigningAccount>> {
@@ -188,42 +574,62 @@ impl ArchwayApp {
         M: ::prost::Message,
         R: ::prost::Message + Default,
     {
-        let msgs = msgs
-            .iter()
-            .map(|(msg, type_url)| {
-                let mut buf = Vec::new();
-                M::encode(msg, &mut buf).map_err(EncodeError::ProtoEncodeError)?;
+        self.execute_multiple_raw_with_granter(encode_msgs(msgs)?, signer, granter)
+    }
 
-                Ok(Any {
-                    type_url: type_url.to_string(),
-                    value: buf,
-                })
-            })
-            .collect::<Result<Vec<Any>, RunnerError>>()?;
+    pub fn execute_multiple_raw_with_granter<R>(
+        &self,
+        msgs: Vec<Any>,
+        signer: &SigningAccount,
+        granter: Option<&str>,
+    ) -> RunnerExecuteResult<R>
+    where
+        R: ::prost::Message + Default,
+    {
+        self.execute_multiple_raw_with_fee_setting(msgs, signer, granter, signer.fee_setting())
+    }
 
-        self.execute_multiple_raw_with_granter(msgs, signer, granter)
+    /// Executes the messages with the given fee setting instead of the signer's one
+    pub fn execute_multiple_with_fee_setting<M, R>(
+        &self,
+        msgs: &[(M, &str)],
+        signer: &SigningAccount,
+        granter: Option<&str>,
+        fee_setting: &FeeSetting,
+    ) -> RunnerExecuteResult<R>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default,
+    {
+        self.execute_multiple_raw_with_fee_setting(encode_msgs(msgs)?, signer, granter, fee_setting)
     }
 
-    pub fn execute_multiple_raw_with_granter<R>(
+    pub fn execute_multiple_raw_with_fee_setting<R>(
         &self,
         msgs: Vec<Any>,
         signer: &SigningAccount,
         granter: Option<&str>,
+        fee_setting: &FeeSetting,
     ) -> RunnerExecuteResult<R>
     where
         R: ::prost::Message + Default,
     {
-        // Set granter for the sim fee
         let mut sim_fee = self.default_simulation_fee();
+        // The ante handler rejects txs that do not cover the executed contracts' flat fees
+        let mut sim_amount = vec![self.default_gas_price()];
+        add_coins(&mut sim_amount, self.flat_fees(&msgs)?);
+        sim_fee.amount = to_fee_amount(sim_amount)?;
+
+        // Set granter for the sim fee
         if let Some(granter) = granter {
-            sim_fee.granter = Some(AccountId::from_str(granter).unwrap())
+            sim_fee.granter = Some(AccountId::from_str(granter)?)
         }
 
         let tx_sim_fee = self.create_signed_tx(msgs.clone(), signer, sim_fee)?;
-        let mut fee = self.calculate_fee(&tx_sim_fee, signer)?;
+        let mut fee = self.calculate_fee_with_setting(&tx_sim_fee, fee_setting)?;
 
         if let Some(granter) = granter {
-            fee.granter = Some(AccountId::from_str(granter).unwrap())
+            fee.granter = Some(AccountId::from_str(granter)?)
         }
 
         let tx = self.create_signed_tx(msgs.clone(), signer, fee)?;
@@ -231,11 +637,70 @@ impl ArchwayApp {
         res.try_into()
     }
 
+    /// Executes the messages with the fee paid by a contract registered as granter in cwfees,
+    /// reporting how much of the fee denom the contract and the signer spent
+    pub fn execute_with_contract_granter<M, R>(
+        &self,
+        msgs: &[(M, &str)],
+        signer: &SigningAccount,
+        granter: &str,
+    ) -> RunnerResult<SponsoredExecuteResponse<R>>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default,
+    {
+        self.execute_with_contract_granter_and_fee_setting(
+            msgs,
+            signer,
+            granter,
+            signer.fee_setting(),
+        )
+    }
+
+    pub fn execute_with_contract_granter_and_fee_setting<M, R>(
+        &self,
+        msgs: &[(M, &str)],
+        signer: &SigningAccount,
+        granter: &str,
+        fee_setting: &FeeSetting,
+    ) -> RunnerResult<SponsoredExecuteResponse<R>>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default,
+    {
+        let is_granting = CwFees::new(self)
+            .is_granting_contract(&IsGrantingContractRequest {
+                contract_address: granter.to_string(),
+            })?
+            .is_granting_contract;
+        if !is_granting {
+            return Err(RunnerError::ExecuteError {
+                msg: format!("{} is not registered as a granting contract", granter),
+            });
+        }
+
+        let response =
+            self.execute_multiple_with_fee_setting(msgs, signer, Some(granter), fee_setting)?;
+
+        // Balances also move with the funds sent by the msgs, so the fee is read from its events
+        let granter_paid = fee_paid_by(&response.events, granter, &self.fee_denom)?;
+        let signer_paid = fee_paid_by(&response.events, &signer.address(), &self.fee_denom)?;
+
+        Ok(SponsoredExecuteResponse {
+            response,
+            granter_paid,
+            signer_paid,
+        })
+    }
+
+    /// Fee of the txs built to be simulated. It ignores the [`GasPriceStrategy`]: the gas limit
+    /// is what the simulation finds out, and without it no gas price gives a meaningful fee,
+    /// the simulated fee only has to be deductible by the payer
     pub fn default_simulation_fee(&self) -> Fee {
         Fee::from_amount_and_gas(
             cosmrs::Coin {
                 denom: self.fee_denom.parse().unwrap(),
-                amount: GAS_PRICE,
+                amount: self.default_gas_price,
             },
             0u64,
         )
@@ -258,50 +723,337 @@ impl ArchwayApp {
         }
     }
 
-    pub fn calculate_fee(&self, tx_bytes: &[u8], fee_payer: &SigningAccount) -> RunnerResult<Fee> {
-        match &fee_payer.fee_setting() {
-            FeeSetting::Auto {
-                gas_price,
-                gas_adjustment,
-            } => {
-                let gas_info = self.simulate_tx_bytes(tx_bytes)?;
-                let gas_limit = ((gas_info.gas_used as f64) * (gas_adjustment)).ceil() as u64;
-                let amount = cosmrs::Coin {
-                    denom: self.fee_denom.parse()?,
-                    amount: (((gas_limit as f64) * (gas_price.amount.u128() as f64)).ceil() as u64)
-                        .into(),
-                };
-
-                Ok(Fee::from_amount_and_gas(amount, gas_limit))
-            }
-            FeeSetting::Custom { amount, gas_limit } => Ok(Fee::from_amount_and_gas(
-                cosmrs::Coin {
-                    denom: amount.denom.parse()?,
-                    amount: amount.amount.to_string().parse().unwrap(),
-                },
-                *gas_limit,
-            )),
-        }
+    /// Dry-runs the messages without committing a block, reporting the gas the tx would use,
+    /// its events and response as well as the fee the signer would pay
+    pub fn simulate<M, R>(
+        &self,
+        msgs: &[(M, &str)],
+        signer: &SigningAccount,
+    ) -> RunnerResult<SimulationResult<R>>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default,
+    {
+        self.simulate_with_fee_setting(msgs, signer, signer.fee_setting())
     }
 
-    fn create_signed_tx<I>(
+    pub fn simulate_raw<R>(
         &self,
-        msgs: I,
+        msgs: Vec<Any>,
         signer: &SigningAccount,
-        fee: Fee,
-    ) -> RunnerResult<Vec<u8>>
+    ) -> RunnerResult<SimulationResult<R>>
     where
-        I: IntoIterator<Item = cosmrs::Any>,
+        R: ::prost::Message + Default,
     {
-        let tx_body = tx::Body::new(msgs, "", 0u32);
-        let addr = signer.address();
+        self.simulate_raw_with_fee_setting(msgs, signer, signer.fee_setting())
+    }
 
-        let seq = self.get_account_sequence(&addr);
-        let account_number = self.get_account_number(&addr);
+    pub fn simulate_with_fee_setting<M, R>(
+        &self,
+        msgs: &[(M, &str)],
+        signer: &SigningAccount,
+        fee_setting: &FeeSetting,
+    ) -> RunnerResult<SimulationResult<R>>
+    where
+        M: ::prost::Message,
+        R: ::prost::Message + Default,
+    {
+        self.simulate_raw_with_fee_setting(encode_msgs(msgs)?, signer, fee_setting)
+    }
 
-        let signer_info = SignerInfo::single_direct(Some(signer.public_key()), seq);
+    pub fn simulate_raw_with_fee_setting<R>(
+        &self,
+        msgs: Vec<Any>,
+        signer: &SigningAccount,
+        fee_setting: &FeeSetting,
+    ) -> RunnerResult<SimulationResult<R>>
+    where
+        R: ::prost::Message + Default,
+    {
+        let mut sim_fee = self.default_simulation_fee();
+        let mut sim_amount = vec![self.default_gas_price()];
+        add_coins(&mut sim_amount, self.flat_fees(&msgs)?);
+        sim_fee.amount = to_fee_amount(sim_amount)?;
 
-        let chain_id = self
+        let tx = self.create_signed_tx(msgs.clone(), signer, sim_fee)?;
+        let base64_tx_bytes = BASE64_STANDARD.encode(tx);
+        redefine_as_go_string!(base64_tx_bytes);
+
+        let res = unsafe {
+            let res = SimulateTx(self.id, base64_tx_bytes);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+        let mut response: ExecuteResponse<R> = ResponseFinalizeBlock::decode(res.as_slice())
+            .map_err(DecodeError::ProtoDecodeError)?
+            .try_into()?;
+
+        let fee = self.fee_for_gas_used(response.gas_info.gas_used, &msgs, fee_setting)?;
+        response.gas_info.gas_wanted = fee.gas_limit;
+
+        Ok(SimulationResult { response, fee })
+    }
+
+    /// Runs a smart query the way a contract querying another one would, so its gas is metered.
+    /// Going over the gas limit errors, `None` leaves the query unbounded
+    pub fn query_smart_with_gas<M, Res>(
+        &self,
+        contract: &str,
+        msg: &M,
+        gas_limit: Option<u64>,
+    ) -> RunnerResult<MeteredQueryResponse<Res>>
+    where
+        M: ?Sized + Serialize,
+        Res: DeserializeOwned,
+    {
+        let gas_limit = gas_limit.unwrap_or_default();
+        let base64_query_msg_bytes =
+            BASE64_STANDARD.encode(serde_json::to_vec(msg).map_err(EncodeError::JsonEncodeError)?);
+        redefine_as_go_string!(contract);
+        redefine_as_go_string!(base64_query_msg_bytes);
+
+        let res = unsafe {
+            let res = QuerySmartWithGas(self.id, contract, base64_query_msg_bytes, gas_limit);
+            RawResult::from_non_null_ptr(res).into_result()?
+        };
+        let res = ExecTxResult::decode(res.as_slice()).map_err(DecodeError::ProtoDecodeError)?;
+
+        Ok(MeteredQueryResponse {
+            data: serde_json::from_slice(&res.data).map_err(DecodeError::JsonDecodeError)?,
+            gas_used: res.gas_used as u64,
+            gas_limit,
+        })
+    }
+
+    pub fn calculate_fee(&self, tx_bytes: &[u8], fee_payer: &SigningAccount) -> RunnerResult<Fee> {
+        self.calculate_fee_with_setting(tx_bytes, fee_payer.fee_setting())
+    }
+
+    pub fn calculate_fee_with_setting(
+        &self,
+        tx_bytes: &[u8],
+        fee_setting: &FeeSetting,
+    ) -> RunnerResult<Fee> {
+        let gas_used = match fee_setting {
+            FeeSetting::Auto { .. } => self.simulate_tx_bytes(tx_bytes)?.gas_used,
+            FeeSetting::Custom { .. } => 0,
+        };
+
+        let tx_raw = TxRaw::decode(tx_bytes).map_err(DecodeError::ProtoDecodeError)?;
+        let body =
+            TxBody::decode(tx_raw.body_bytes.as_slice()).map_err(DecodeError::ProtoDecodeError)?;
+        self.fee_for_gas_used(gas_used, &body.messages, fee_setting)
+    }
+
+    /// Fee paid by a tx of the messages that used the given amount of gas
+    pub fn fee_for_gas_used(
+        &self,
+        gas_used: u64,
+        msgs: &[Any],
+        fee_setting: &FeeSetting,
+    ) -> RunnerResult<Fee> {
+        match fee_setting {
+            FeeSetting::Auto {
+                gas_price,
+                gas_adjustment,
+            } => {
+                let gas_limit = ((gas_used as f64) * gas_adjustment).ceil() as u64;
+                let gas_price = self.gas_price(gas_price)?;
+                let gas_fee = Coin::new(
+                    Uint128::try_from(
+                        (Decimal256::from_ratio(gas_limit, 1u128) * gas_price.amount)
+                            .to_uint_ceil(),
+                    )
+                    .map_err(|e| RunnerError::ExecuteError { msg: e.to_string() })?,
+                    gas_price.denom,
+                );
+
+                // The estimation includes the minimum consensus fee, which might be higher
+                let mut amount = vec![gas_fee];
+                for coin in self.estimate_tx_fees(gas_limit, None)? {
+                    match amount.iter_mut().find(|c| c.denom == coin.denom) {
+                        Some(existing) => existing.amount = existing.amount.max(coin.amount),
+                        None => amount.push(coin),
+                    }
+                }
+
+                // Flat fees are charged on top of the gas fee for every executed contract
+                add_coins(&mut amount, self.flat_fees(msgs)?);
+
+                Ok(Fee {
+                    amount: to_fee_amount(amount)?,
+                    gas_limit,
+                    payer: None,
+                    granter: None,
+                })
+            }
+            FeeSetting::Custom { amount, gas_limit } => Ok(Fee::from_amount_and_gas(
+                cosmrs::Coin {
+                    denom: amount.denom.parse()?,
+                    amount: amount.amount.to_string().parse().unwrap(),
+                },
+                *gas_limit,
+            )),
+        }
+    }
+
+    /// Estimates the fee for the given gas limit using the minimum consensus fee,
+    /// including the flat fee of the contract when given
+    pub fn estimate_tx_fees(
+        &self,
+        gas_limit: u64,
+        contract: Option<&str>,
+    ) -> RunnerResult<Vec<Coin>> {
+        let res = Rewards::new(self).query_estimate_tx_fees(&QueryEstimateTxFeesRequest {
+            gas_limit,
+            contract_address: contract.unwrap_or_default().to_string(),
+        })?;
+
+        Ok(res
+            .estimated_fee
+            .iter()
+            .map(try_from_proto)
+            .collect::<RunnerResult<Vec<_>>>()?
+            .into_iter()
+            .filter(|coin| !coin.amount.is_zero())
+            .collect())
+    }
+
+    /// Queries the flat fee charged on every execution of the contract
+    pub fn contract_flat_fee(&self, contract: &str) -> RunnerResult<Vec<Coin>> {
+        self.estimate_tx_fees(0, Some(contract))
+    }
+
+    /// Reads the whole raw storage of the contract, diff two snapshots with [`ContractState::diff`]
+    pub fn contract_state_snapshot(&self, contract: &str) -> RunnerResult<ContractState> {
+        let wasm = Wasm::new(self);
+        let mut snapshot = ContractState {
+            contract: contract.to_string(),
+            height: self.get_block_height()?,
+            ..Default::default()
+        };
+
+        let mut next_key = vec![];
+        loop {
+            let res = wasm.all_contract_state(
+                contract,
+                Some(PageRequest {
+                    key: next_key,
+                    ..Default::default()
+                }),
+            )?;
+            snapshot
+                .entries
+                .extend(res.models.into_iter().map(|model| (model.key, model.value)));
+
+            match res.pagination {
+                Some(pagination) if !pagination.next_key.is_empty() => {
+                    next_key = pagination.next_key
+                }
+                _ => break,
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Address of a module account, e.g. `gov` which is the authority of most params
+    pub fn module_address(&self, module_name: &str) -> RunnerResult<String> {
+        let hash = Sha256::digest(module_name.as_bytes());
+        Ok(AccountId::new(&self.address_prefix, &hash[..20])?.to_string())
+    }
+
+    /// Schedules a software upgrade at the given height through a gov proposal passed by the validator.
+    /// The height must be past the block ending the voting period, apply it with [`ArchwayApp::apply_upgrade`]
+    pub fn schedule_upgrade(&self, name: &str, height: i64) -> RunnerResult<()> {
+        // Reaching the height of an upgrade without a handler halts the chain
+        if !self.upgrade_names()?.iter().any(|upgrade| upgrade == name) {
+            return Err(RunnerError::ExecuteError {
+                msg: format!("no handler for the {} upgrade in this archway build", name),
+            });
+        }
+
+        let validator = self.get_first_validator_signing_account()?;
+        let msg = MsgSoftwareUpgrade {
+            authority: self.module_address("gov")?,
+            plan: Some(Plan {
+                name: name.to_string(),
+                height,
+                ..Default::default()
+            }),
+        };
+
+        GovWithAppAccess::new(self).propose_and_execute_msgs(
+            vec![ProtoAny {
+                type_url: MsgSoftwareUpgrade::type_url(),
+                value: msg.encode_to_vec(),
+            }],
+            format!("Upgrade {}", name),
+            format!("Software upgrade {} at height {}", name, height),
+            validator.address(),
+            &validator,
+        )?;
+
+        // A failing proposal does not fail its submission
+        let plan = Upgrade::new(self)
+            .query_current_plan(&QueryCurrentPlanRequest {})?
+            .plan;
+        match plan {
+            Some(plan) if plan.name == name => Ok(()),
+            _ => Err(RunnerError::ExecuteError {
+                msg: format!("the proposal did not schedule the {} upgrade", name),
+            }),
+        }
+    }
+
+    /// Commits empty blocks up to the height of the scheduled upgrade, where the upgrade module runs the
+    /// handler the Archway app registers under its name, and returns that height.
+    /// The env starts from the genesis of this Archway build, so the module versions are already current
+    /// and only the handler's own changes, such as param updates, take effect.
+    pub fn apply_upgrade(&self, name: &str) -> RunnerResult<i64> {
+        redefine_as_go_string!(name);
+
+        unsafe { decode_json_result(ApplyUpgrade(self.id, name)) }
+    }
+
+    /// Names of the upgrades the Archway build has handlers for, oldest first
+    pub fn upgrade_names(&self) -> RunnerResult<Vec<String>> {
+        unsafe { decode_json_result(UpgradeNames()) }
+    }
+
+    /// Sums the flat fees of every contract directly executed by the messages
+    pub fn flat_fees(&self, msgs: &[Any]) -> RunnerResult<Vec<Coin>> {
+        let mut fees = vec![];
+        for msg in msgs
+            .iter()
+            .filter(|msg| msg.type_url == MsgExecuteContract::type_url())
+        {
+            let contract = MsgExecuteContract::decode(msg.value.as_slice())
+                .map_err(DecodeError::ProtoDecodeError)?
+                .contract;
+            add_coins(&mut fees, self.contract_flat_fee(&contract)?);
+        }
+
+        Ok(fees)
+    }
+
+    fn create_signed_tx<I>(
+        &self,
+        msgs: I,
+        signer: &SigningAccount,
+        fee: Fee,
+    ) -> RunnerResult<Vec<u8>>
+    where
+        I: IntoIterator<Item = cosmrs::Any>,
+    {
+        let tx_body = tx::Body::new(msgs, "", 0u32);
+        let addr = signer.address();
+
+        let seq = self.get_account_sequence(&addr)?;
+        let account_number = self.get_account_number(&addr)?;
+
+        let signer_info = SignerInfo::single_direct(Some(signer.public_key()), seq);
+
+        let chain_id = self
             .chain_id
             .parse()
             .expect("parse const str of chain id should never fail");
@@ -321,6 +1073,110 @@ impl ArchwayApp {
     }
 }
 
+fn coins_to_json(coins: &[Coin]) -> RunnerResult<String> {
+    let mut coins = coins.to_vec();
+
+    // invalid coins if denom are unsorted
+    coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    serde_json::to_string(&coins)
+        .map_err(EncodeError::JsonEncodeError)
+        .map_err(RunnerError::EncodeError)
+}
+
+/// Decodes the json payload of a result returned by libarchway
+unsafe fn decode_json_result<T: DeserializeOwned>(res: *mut c_char) -> RunnerResult<T> {
+    let res = RawResult::from_non_null_ptr(res).into_result()?;
+    Ok(serde_json::from_slice(&res).map_err(DecodeError::JsonDecodeError)?)
+}
+
+/// Decodes the json value of the first attribute with the key among the events of the type
+fn json_event_attribute<T: DeserializeOwned>(
+    events: &[Event],
+    ty: &str,
+    key: &str,
+) -> RunnerResult<Option<T>> {
+    let Some(attribute) = events
+        .iter()
+        .filter(|event| event.ty == ty)
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key == key)
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(
+        serde_json::from_str(&attribute.value).map_err(DecodeError::JsonDecodeError)?,
+    ))
+}
+
+/// Amount of the denom the payer was charged as tx fee, from the `tx` events of the ante handler
+fn fee_paid_by(events: &[Event], payer: &str, denom: &str) -> RunnerResult<u128> {
+    let mut paid = 0;
+    for event in events.iter().filter(|event| event.ty == "tx") {
+        let attribute = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .map(|attribute| attribute.value.as_str())
+        };
+        if attribute("fee_payer") != Some(payer) {
+            continue;
+        }
+
+        if let Some(fee) = attribute("fee") {
+            paid += from_coins_str(fee)?
+                .iter()
+                .filter(|coin| coin.denom == denom)
+                .map(|coin| coin.amount.u128())
+                .sum::<u128>();
+        }
+    }
+
+    Ok(paid)
+}
+
+fn encode_msgs<M>(msgs: &[(M, &str)]) -> RunnerResult<Vec<Any>>
+where
+    M: ::prost::Message,
+{
+    msgs.iter()
+        .map(|(msg, type_url)| {
+            let mut buf = Vec::new();
+            M::encode(msg, &mut buf).map_err(EncodeError::ProtoEncodeError)?;
+
+            Ok(Any {
+                type_url: type_url.to_string(),
+                value: buf,
+            })
+        })
+        .collect()
+}
+
+fn add_coins(amount: &mut Vec<Coin>, coins: Vec<Coin>) {
+    for coin in coins {
+        match amount.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => existing.amount += coin.amount,
+            None => amount.push(coin),
+        }
+    }
+}
+
+/// Converts the coins into a fee amount, which must be sorted by denom
+fn to_fee_amount(mut coins: Vec<Coin>) -> RunnerResult<Vec<cosmrs::Coin>> {
+    coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+    coins
+        .into_iter()
+        .map(|coin| {
+            Ok(cosmrs::Coin {
+                denom: coin.denom.parse()?,
+                amount: coin.amount.u128(),
+            })
+        })
+        .collect()
+}
+
 impl Default for ArchwayApp {
     fn default() -> Self {
         Self::new()
@@ -356,11 +1212,9 @@ impl<'a> Runner<'a> for ArchwayApp {
             let base64_tx = BASE64_STANDARD.encode(tx_bytes);
             redefine_as_go_string!(base64_tx);
 
-            let res = FinalizeBlock(self.id, base64_tx);
+            let res = FinalizeAndCommit(self.id, base64_tx);
             let res = RawResult::from_non_null_ptr(res).into_result()?;
 
-            RawResult::from_non_null_ptr(Commit(self.id)).into_result()?;
-
             let res = ResponseFinalizeBlock::decode(res.as_slice())
                 .map_err(DecodeError::ProtoDecodeError)?;
 
@@ -405,17 +1259,44 @@ impl<'a> Runner<'a> for ArchwayApp {
 
 #[cfg(test)]
 mod tests {
-    use crate::module::{Authz, Distribution, Staking, Wasm};
-    use crate::{arch, to_proto, ArchwayApp};
+    use crate::gas::GasBreakdown;
+    use crate::ibc::{IbcCoordinator, RelayReport, Relayer};
+    use crate::log::{set_log_sink, DebugMessage, LogLevel, LogSink};
+    use crate::module::{
+        contract_grant, Authz, Bank, CallbackOutcome, CallbackWithAppAccess, CwErrors,
+        Distribution, ErrorModule, GrantFilter, GrantLimit, RewardsWithAppAccess, Staking,
+        Transfer, Upgrade, Wasm,
+    };
+    use crate::storage::{StorageAccess, StorageOperation};
+    use crate::{
+        arch, fee_paid_by, to_proto, ArchwayApp, GasPriceStrategy, CHAIN_ID, DEFAULT_GAS_PRICE,
+        DEFAULT_HD_PATH, DEFAULT_SMART_QUERY_GAS_LIMIT,
+    };
+    use archway_proto::archway::cwfees::v1::MsgRegisterAsGranter;
+    use archway_proto::archway::cwica::v1::{MsgRegisterInterchainAccount, MsgSendTx};
+    use archway_proto::archway::rewards::v1::{MsgSetFlatFee, QueryParamsRequest};
+    use archway_proto::cosmos::bank::v1beta1::{
+        MsgSend, MsgSendResponse, QueryDenomMetadataRequest,
+    };
     use archway_proto::cosmos::distribution::v1beta1::MsgWithdrawDelegatorReward;
     use archway_proto::cosmos::staking::v1beta1::{AuthorizationType, MsgDelegate};
+    use archway_proto::cosmos::upgrade::v1beta1::QueryAppliedPlanRequest;
+    use archway_proto::cosmwasm::wasm::v1::{
+        ContractMigrationAuthorization, MsgExecuteContract, MsgExecuteContractResponse,
+        MsgMigrateContract,
+    };
+    use archway_proto::tendermint::google::protobuf::Any as ProtoAny;
+    use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+    use base64::Engine;
     use cosmwasm_schema::cw_serde;
-    use cosmwasm_std::{coins, Coin};
-    use prost::Name;
+    use cosmwasm_std::{coins, Coin, DecCoin, Decimal256, Uint128, Uint256};
+    use prost::{Message, Name};
     use serde::Serialize;
     use std::option::Option::None;
+    use std::str::FromStr;
     use test_tube::account::Account;
     use test_tube::module::Module;
+    use test_tube::{FeeSetting, SigningAccount};
 
     pub mod netwars_msgs {
         use cosmwasm_std::{Addr, Uint128};
@@ -548,20 +1429,297 @@ mod tests {
         assert!(accounts.get(3).is_none());
     }
 
+    #[test]
+    fn test_deterministic_accounts() {
+        let mnemonic = "notice oak worry limit wrap speak medal online prefer cluster roof addict wrist behave treat actual wasp year salad speed social layer crew genius";
+
+        let app_a = ArchwayApp::default();
+        let app_b = ArchwayApp::default();
+
+        let seeded_a = app_a.init_account_from_seed("alice", &[arch(1)]).unwrap();
+        let seeded_b = app_b.init_account_from_seed("alice", &[arch(1)]).unwrap();
+        assert_eq!(seeded_a.address(), seeded_b.address());
+        assert_ne!(
+            seeded_a.address(),
+            app_a
+                .init_account_from_seed("bob", &[arch(1)])
+                .unwrap()
+                .address()
+        );
+
+        let first = app_a
+            .init_account_from_mnemonic(mnemonic, DEFAULT_HD_PATH, &[arch(1)])
+            .unwrap();
+        let second = app_a
+            .init_account_from_mnemonic(mnemonic, "m/44'/118'/0'/0/1", &[arch(1)])
+            .unwrap();
+        assert_ne!(first.address(), second.address());
+        assert_eq!(
+            first.address(),
+            app_b
+                .init_account_from_mnemonic(mnemonic, DEFAULT_HD_PATH, &[arch(1)])
+                .unwrap()
+                .address()
+        );
+        assert!(app_a
+            .init_account_from_mnemonic("not a mnemonic", DEFAULT_HD_PATH, &[arch(1)])
+            .is_err());
+
+        let mut app_c = ArchwayApp::default();
+        let mut app_d = ArchwayApp::default();
+        app_c.set_account_seed(Some("meter".to_string()));
+        app_d.set_account_seed(Some("meter".to_string()));
+        let accounts_c = app_c.init_accounts(&[arch(1)], 2).unwrap();
+        let accounts_d = app_d.init_accounts(&[arch(1)], 2).unwrap();
+        assert_eq!(accounts_c[0].address(), accounts_d[0].address());
+        assert_eq!(accounts_c[1].address(), accounts_d[1].address());
+        assert_ne!(accounts_c[0].address(), accounts_c[1].address());
+    }
+
     #[test]
     fn test_get_and_set_block_timestamp() {
         let app = ArchwayApp::default();
 
-        let block_time_nanos = app.get_block_time_nanos();
-        let block_time_seconds = app.get_block_time_seconds();
+        let block_time_nanos = app.get_block_time_nanos().unwrap();
+        let block_time_seconds = app.get_block_time_seconds().unwrap();
 
-        app.increase_time(10u64);
+        app.increase_time(10u64).unwrap();
 
         assert_eq!(
-            app.get_block_time_nanos(),
+            app.get_block_time_nanos().unwrap(),
             block_time_nanos + 10_000_000_000
         );
-        assert_eq!(app.get_block_time_seconds(), block_time_seconds + 10);
+        assert_eq!(
+            app.get_block_time_seconds().unwrap(),
+            block_time_seconds + 10
+        );
+    }
+
+    #[test]
+    fn test_ffi_errors() {
+        let app = ArchwayApp::default();
+        assert!(app.init_account(&[Coin::new(1u128, "!invalid")]).is_err());
+        assert!(app.get_account_sequence("not an address").is_err());
+
+        let missing = ArchwayApp::from_env(u64::MAX, CHAIN_ID);
+        let err = missing.get_block_height().unwrap_err();
+        assert!(err.to_string().contains("env not found"));
+        assert!(missing.get_block_time_nanos().is_err());
+        assert!(missing.skip_block().is_err());
+        assert!(missing.increase_time(5).is_err());
+        assert!(missing.init_account(&[]).is_err());
+
+        // Errors leave the app usable
+        let height = app.get_block_height().unwrap();
+        app.skip_block().unwrap();
+        assert_eq!(app.get_block_height().unwrap(), height + 1);
+    }
+
+    #[test]
+    fn test_log_sink() {
+        // The sink is shared with the apps of the tests running in parallel, so the default sink
+        // is restored right after creating the app, even if creating it panics
+        struct RestoreDefaultSink;
+        impl Drop for RestoreDefaultSink {
+            fn drop(&mut self) {
+                set_log_sink(&LogSink::Discard, LogLevel::Info).unwrap();
+            }
+        }
+
+        let path = std::env::temp_dir().join(format!("litmus-{}.log", std::process::id()));
+        let restore = RestoreDefaultSink;
+        set_log_sink(&LogSink::File(path.clone()), LogLevel::Info).unwrap();
+        let app = ArchwayApp::default();
+        drop(restore);
+
+        // The app keeps the sink it was created with
+        app.skip_block().unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().is_empty());
+        std::fs::remove_file(path).unwrap();
+
+        let missing_dir = std::env::temp_dir()
+            .join("litmus-missing-dir")
+            .join("chain.log");
+        assert!(set_log_sink(&LogSink::File(missing_dir), LogLevel::Info).is_err());
+    }
+
+    #[test]
+    fn test_log_capture() {
+        let mut app = ArchwayApp::default();
+        app.skip_block().unwrap();
+        assert!(app.take_logs().unwrap().is_empty());
+
+        app.set_log_capture(Some(LogLevel::Info)).unwrap();
+        app.skip_block().unwrap();
+        let logs = app.take_logs().unwrap();
+        assert!(!logs.is_empty());
+        assert!(logs
+            .iter()
+            .all(|entry| entry.level.as_deref() != Some("debug")));
+        // Taking the logs drains them
+        assert!(app.take_logs().unwrap().is_empty());
+
+        app.set_log_capture(None).unwrap();
+        app.skip_block().unwrap();
+        assert!(app.take_logs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_calls() {
+        use cw1_whitelist::msg::*;
+
+        let app = ArchwayApp::default();
+        let senders = app.init_accounts(&[arch(100)], 4).unwrap();
+        let receiver = app.init_account(&[arch(1)]).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/cw1_whitelist.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, &senders[0])
+            .unwrap()
+            .data
+            .code_id;
+        let contract_addr = wasm
+            .instantiate(
+                code_id,
+                &InstantiateMsg {
+                    admins: vec![senders[0].address()],
+                    mutable: false,
+                },
+                None,
+                Some("cw1_whitelist"),
+                &[],
+                &senders[0],
+            )
+            .unwrap()
+            .data
+            .address;
+        let start_height = app.get_block_height().unwrap();
+
+        std::thread::scope(|scope| {
+            for sender in senders.iter() {
+                let (app, receiver) = (&app, &receiver);
+                scope.spawn(move || {
+                    for _ in 0..3 {
+                        Bank::new(app)
+                            .send(
+                                MsgSend {
+                                    from_address: sender.address(),
+                                    to_address: receiver.address(),
+                                    amount: vec![to_proto(&arch(1))],
+                                },
+                                sender,
+                            )
+                            .unwrap();
+                    }
+                });
+            }
+
+            for _ in 0..4 {
+                let (app, contract_addr) = (&app, &contract_addr);
+                scope.spawn(move || {
+                    for _ in 0..3 {
+                        let admin_list = Wasm::new(app)
+                            .query::<QueryMsg, AdminListResponse>(
+                                contract_addr,
+                                &QueryMsg::AdminList {},
+                            )
+                            .unwrap();
+                        assert_eq!(admin_list.admins.len(), 1);
+                        app.get_block_height().unwrap();
+                    }
+                });
+            }
+        });
+
+        let balance = Bank::new(&app)
+            .balance(receiver.address(), "aarch")
+            .unwrap()
+            .balance
+            .unwrap()
+            .amount
+            .parse::<u128>()
+            .unwrap();
+        assert_eq!(balance, arch(13).amount.u128());
+        // Every tx was finalized and committed in a block of its own
+        assert_eq!(app.get_block_height().unwrap(), start_height + 12);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_app() {
+        use crate::AsyncArchwayApp;
+        use cw1_whitelist::msg::*;
+
+        let app = AsyncArchwayApp::new().await.unwrap();
+        let accounts = app.init_accounts(&[arch(100)], 2).await.unwrap();
+        let admin = accounts[0].clone();
+        let receiver = accounts[1].clone();
+
+        let contract_addr = app
+            .run(move |app| {
+                let wasm = Wasm::new(&*app);
+                let wasm_byte_code = std::fs::read("./test_artifacts/cw1_whitelist.wasm").unwrap();
+                let code_id = wasm
+                    .store_code(&wasm_byte_code, None, &admin)
+                    .unwrap()
+                    .data
+                    .code_id;
+                wasm.instantiate(
+                    code_id,
+                    &InstantiateMsg {
+                        admins: vec![admin.address()],
+                        mutable: true,
+                    },
+                    None,
+                    Some("cw1_whitelist"),
+                    &[],
+                    &admin,
+                )
+                .unwrap()
+                .data
+                .address
+            })
+            .await;
+
+        let send = MsgSend {
+            from_address: accounts[0].address(),
+            to_address: receiver.address(),
+            amount: vec![to_proto(&arch(1))],
+        };
+        let type_url = MsgSend::type_url();
+        let simulation = app
+            .simulate::<_, MsgSendResponse>(&[(send.clone(), type_url.as_str())], &accounts[0])
+            .await
+            .unwrap();
+        assert!(simulation.response.gas_info.gas_used > 0);
+
+        let height = app.get_block_height().await.unwrap();
+        let (sent, admin_list, skipped) = tokio::join!(
+            app.execute::<_, MsgSendResponse>(send, &type_url, &accounts[0]),
+            app.wasm_query::<_, AdminListResponse>(&contract_addr, &QueryMsg::AdminList {}),
+            app.skip_blocks(2),
+        );
+        sent.unwrap();
+        skipped.unwrap();
+        assert_eq!(admin_list.unwrap().admins, vec![accounts[0].address()]);
+        assert_eq!(app.get_block_height().await.unwrap(), height + 3);
+
+        app.wasm_execute::<ExecuteMsg>(
+            &contract_addr,
+            &ExecuteMsg::UpdateAdmins {
+                admins: vec![receiver.address()],
+            },
+            &[],
+            &accounts[0],
+        )
+        .await
+        .unwrap();
+        let admin_list = app
+            .wasm_query::<_, AdminListResponse>(&contract_addr, &QueryMsg::AdminList {})
+            .await
+            .unwrap();
+        assert_eq!(admin_list.admins, vec![receiver.address()]);
     }
 
     #[test]
@@ -569,11 +1727,11 @@ mod tests {
         let app = ArchwayApp::default();
 
         // Governance transactions fix
-        assert_eq!(app.get_block_height(), 1i64);
+        assert_eq!(app.get_block_height().unwrap(), 1i64);
 
-        app.increase_time(10u64);
+        app.increase_time(10u64).unwrap();
 
-        assert_eq!(app.get_block_height(), 2i64);
+        assert_eq!(app.get_block_height().unwrap(), 2i64);
     }
 
     #[test]
@@ -646,47 +1804,1412 @@ mod tests {
     }
 
     #[test]
-    fn test_authz() {
+    fn test_contract_state_diff() {
+        use cw1_whitelist::msg::*;
+        use cw1_whitelist::state::AdminList;
+
         let app = ArchwayApp::default();
-        let accounts = app.init_accounts(&[arch(100)], 2).unwrap();
-        let grantee = accounts.get(0).unwrap();
-        let granter = accounts.get(1).unwrap();
+        let accs = app.init_accounts(&[arch(100)], 2).unwrap();
+        let admin = &accs[0];
+        let new_admin = &accs[1];
 
-        let authz = Authz::new(&app);
-        authz
-            .grant_generic(
-                &granter,
-                grantee.address(),
-                MsgWithdrawDelegatorReward::type_url(),
-                None,
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/cw1_whitelist.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, admin)
+            .unwrap()
+            .data
+            .code_id;
+        let contract_addr = wasm
+            .instantiate(
+                code_id,
+                &InstantiateMsg {
+                    admins: vec![admin.address()],
+                    mutable: true,
+                },
+                Some(&admin.address()),
+                Some("cw1_whitelist"),
+                &[],
+                admin,
             )
-            .unwrap();
+            .unwrap()
+            .data
+            .address;
 
-        authz
-            .grant_stake(
-                &granter,
-                grantee.address(),
-                AuthorizationType::Delegate,
-                None,
-                None,
-                None,
-            )
+        let before = app.contract_state_snapshot(&contract_addr).unwrap();
+        let admin_list: AdminList = before.get_json("admin_list").unwrap().unwrap();
+        assert_eq!(admin_list.admins, vec![admin.address()]);
+        assert!(before.get("contract_info").is_some());
+        assert!(before.diff(&before).is_empty());
+
+        wasm.execute::<ExecuteMsg>(
+            &contract_addr,
+            &ExecuteMsg::UpdateAdmins {
+                admins: vec![new_admin.address()],
+            },
+            &[],
+            admin,
+        )
+        .unwrap();
+
+        let after = app.contract_state_snapshot(&contract_addr).unwrap();
+        assert!(after.height > before.height);
+
+        let diff = before.diff(&after);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+
+        let change = diff.get_changed("admin_list").unwrap();
+        let old: AdminList = change.before_json().unwrap();
+        let new: AdminList = change.after_json().unwrap();
+        assert_eq!(old.admins, vec![admin.address()]);
+        assert_eq!(new.admins, vec![new_admin.address()]);
+        assert_eq!(
+            diff.bytes_written(),
+            ("admin_list".len() + change.after.len()) as u64
+        );
+
+        let reverted = after.diff(&before);
+        assert_eq!(reverted.changed[0].after, change.before);
+    }
+
+    #[test]
+    fn test_query_smart_with_gas() {
+        use cw1_whitelist::msg::*;
+
+        let app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/cw1_whitelist.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, &admin)
+            .unwrap()
+            .data
+            .code_id;
+        let contract_addr = wasm
+            .instantiate(
+                code_id,
+                &InstantiateMsg {
+                    admins: vec![admin.address()],
+                    mutable: true,
+                },
+                None,
+                Some("cw1_whitelist"),
+                &[],
+                &admin,
+            )
+            .unwrap()
+            .data
+            .address;
+
+        let res = app
+            .query_smart_with_gas::<QueryMsg, AdminListResponse>(
+                &contract_addr,
+                &QueryMsg::AdminList {},
+                Some(DEFAULT_SMART_QUERY_GAS_LIMIT),
+            )
+            .unwrap();
+        assert_eq!(res.data.admins, vec![admin.address()]);
+        assert!(res.gas_used > 0);
+        assert!(res.gas_used <= res.gas_limit);
+
+        // Metering does not change the response nor the gas between calls
+        let unbounded = app
+            .query_smart_with_gas::<QueryMsg, AdminListResponse>(
+                &contract_addr,
+                &QueryMsg::AdminList {},
+                None,
+            )
+            .unwrap();
+        assert_eq!(unbounded.data, res.data);
+        assert_eq!(unbounded.gas_used, res.gas_used);
+        assert_eq!(unbounded.gas_limit, 0);
+
+        let err = app
+            .query_smart_with_gas::<QueryMsg, AdminListResponse>(
+                &contract_addr,
+                &QueryMsg::AdminList {},
+                Some(res.gas_used - 1),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("out of gas"));
+    }
+
+    #[test]
+    fn test_authz() {
+        let app = ArchwayApp::default();
+        let accounts = app.init_accounts(&[arch(100)], 2).unwrap();
+        let grantee = accounts.get(0).unwrap();
+        let granter = accounts.get(1).unwrap();
+
+        let authz = Authz::new(&app);
+        authz
+            .grant_generic(
+                &granter,
+                grantee.address(),
+                MsgWithdrawDelegatorReward::type_url(),
+                None,
+            )
+            .unwrap();
+
+        authz
+            .grant_stake(
+                &granter,
+                grantee.address(),
+                AuthorizationType::Delegate,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_authz_exec_and_revoke() {
+        let app = ArchwayApp::default();
+        let accounts = app.init_accounts(&[arch(100)], 3).unwrap();
+        let grantee = accounts.get(0).unwrap();
+        let granter = accounts.get(1).unwrap();
+        let receiver = accounts.get(2).unwrap();
+
+        let authz = Authz::new(&app);
+        authz
+            .grant_send(&granter, grantee.address(), vec![], vec![arch(10)], None)
+            .unwrap();
+
+        let grants = authz
+            .grants(granter.address(), grantee.address(), None, None)
+            .unwrap();
+        assert_eq!(grants.grants.len(), 1);
+        assert_eq!(
+            authz
+                .grantee_grants(grantee.address(), None)
+                .unwrap()
+                .grants
+                .len(),
+            1
+        );
+
+        authz
+            .exec_msgs(
+                &grantee,
+                vec![MsgSend {
+                    from_address: granter.address(),
+                    to_address: receiver.address(),
+                    amount: vec![to_proto(&arch(5))],
+                }
+                .into()],
+            )
+            .unwrap();
+
+        let balance = Bank::new(&app)
+            .balance(receiver.address(), "aarch")
+            .unwrap()
+            .balance
+            .unwrap()
+            .amount
+            .parse::<u128>()
+            .unwrap();
+        assert_eq!(balance, arch(105).amount.u128());
+
+        authz
+            .revoke(&granter, grantee.address(), MsgSend::type_url())
+            .unwrap();
+        assert!(authz
+            .granter_grants(granter.address(), None)
+            .unwrap()
+            .grants
+            .is_empty());
+        assert!(authz
+            .exec_msgs(
+                &grantee,
+                vec![MsgSend {
+                    from_address: granter.address(),
+                    to_address: receiver.address(),
+                    amount: vec![to_proto(&arch(1))],
+                }
+                .into()],
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_authz_contract_grants() {
+        let app = ArchwayApp::default();
+        let accounts = app.init_accounts(&[arch(100)], 2).unwrap();
+        let grantee = accounts.get(0).unwrap();
+        let granter = accounts.get(1).unwrap();
+
+        let granted = instantiate_sudo_recorder(&app, granter);
+        let other = instantiate_sudo_recorder(&app, granter);
+
+        let authz = Authz::new(&app);
+        authz
+            .grant_contract_execution(
+                granter,
+                grantee.address(),
+                vec![
+                    contract_grant(&granted, GrantLimit::MaxCalls(1), GrantFilter::AllowAll)
+                        .unwrap(),
+                ],
+                None,
+            )
+            .unwrap();
+
+        let execute = |contract: &str| {
+            authz.exec_msgs(
+                grantee,
+                vec![MsgExecuteContract {
+                    sender: granter.address(),
+                    contract: contract.to_string(),
+                    msg: br#"{"messages":[]}"#.to_vec(),
+                    funds: vec![],
+                }
+                .into()],
+            )
+        };
+
+        // Only the granted contract can be executed
+        assert!(execute(&other).is_err());
+        execute(&granted).unwrap();
+
+        // The single call allowed is used up, which removes the grant
+        assert!(execute(&granted).is_err());
+        assert!(authz
+            .grants(
+                granter.address(),
+                grantee.address(),
+                Some(MsgExecuteContract::type_url()),
+                None
+            )
+            .unwrap()
+            .grants
+            .is_empty());
+
+        authz
+            .grant_contract_migration(
+                granter,
+                grantee.address(),
+                vec![contract_grant(
+                    &granted,
+                    GrantLimit::MaxCalls(1),
+                    GrantFilter::AcceptedKeys(vec!["migrate".to_string()]),
+                )
+                .unwrap()],
+                None,
+            )
+            .unwrap();
+        let grants = authz
+            .grants(
+                granter.address(),
+                grantee.address(),
+                Some(MsgMigrateContract::type_url()),
+                None,
+            )
+            .unwrap()
+            .grants;
+        assert_eq!(grants.len(), 1);
+        assert_eq!(
+            grants[0].authorization.as_ref().unwrap().type_url,
+            ContractMigrationAuthorization::type_url()
+        );
+    }
+
+    #[test]
+    fn test_contract_rewards() {
+        let app = ArchwayApp::default();
+        let accounts = app.init_accounts(&[arch(100)], 2).unwrap();
+        let admin = accounts.get(0).unwrap();
+        let rewards_owner = accounts.get(1).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/low_gas_demo.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, admin)
+            .unwrap()
+            .data
+            .code_id;
+
+        #[derive(Serialize)]
+        struct InstMsg {}
+
+        let contract_addr = wasm
+            .instantiate(
+                code_id,
+                &InstMsg {},
+                Some(&admin.address()),
+                Some("test_contract"),
+                &[],
+                admin,
+            )
+            .unwrap()
+            .data
+            .address;
+
+        let rewards = RewardsWithAppAccess::new(&app);
+        rewards
+            .set_rewards_address(&contract_addr, admin, rewards_owner.address())
+            .unwrap();
+
+        #[cw_serde]
+        pub enum ExecMsg {
+            EmptyLoad {},
+        }
+
+        let res = wasm
+            .execute(&contract_addr, &ExecMsg::EmptyLoad {}, &[], admin)
+            .unwrap();
+        let fee = fee_paid_by(&res.events, &admin.address(), "aarch").unwrap();
+
+        let report = rewards.last_tx_report(&contract_addr).unwrap();
+        assert!(report.contract_gas > 0);
+
+        // The contract is the only one of the tx, so it gets the whole fee rebate
+        let rebate_ratio = rewards
+            .to_rewards()
+            .query_params(&QueryParamsRequest {})
+            .unwrap()
+            .params
+            .unwrap()
+            .tx_fee_rebate_ratio;
+        let rebate_ratio = match rebate_ratio.contains('.') {
+            true => Decimal256::from_str(&rebate_ratio).unwrap(),
+            false => {
+                Decimal256::from_atomics(Uint256::from_str(&rebate_ratio).unwrap(), 18).unwrap()
+            }
+        };
+        let fee_rebate = (Decimal256::from_ratio(fee, 1u128) * rebate_ratio).to_uint_floor();
+        assert_eq!(
+            report.fee_rewards,
+            vec![Coin::new(Uint128::try_from(fee_rebate).unwrap(), "aarch")]
+        );
+
+        // Reporting does not change the chain
+        assert_eq!(app.get_block_height().unwrap(), report.height);
+
+        // The following block pays both the fee rebate and the inflation rewards
+        app.skip_block().unwrap();
+        let mut expected = report.fee_rewards[0].clone();
+        if let Some(inflation) = report.inflation_rewards.first() {
+            expected.amount += inflation.amount;
+        }
+        assert_eq!(
+            rewards.paid_rewards(&contract_addr, report.height).unwrap(),
+            vec![expected]
+        );
+
+        // Nothing is paid to addresses without rewards metadata
+        assert_eq!(
+            rewards
+                .paid_rewards(&admin.address(), report.height)
+                .unwrap(),
+            vec![]
+        );
+
+        let res = rewards.accrue_and_withdraw(rewards_owner, 1).unwrap();
+        assert!(res.data.records_num > 0);
+        assert!(!res.data.total_rewards.is_empty());
+    }
+
+    #[test]
+    fn test_flat_fee_execution() {
+        let app = ArchwayApp::default();
+        let accounts = app.init_accounts(&[arch(100)], 2).unwrap();
+        let admin = accounts.get(0).unwrap();
+        let user = accounts.get(1).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/low_gas_demo.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, admin)
+            .unwrap()
+            .data
+            .code_id;
+
+        #[derive(Serialize)]
+        struct InstMsg {}
+
+        let contract_addr = wasm
+            .instantiate(
+                code_id,
+                &InstMsg {},
+                Some(&admin.address()),
+                Some("test_contract"),
+                &[],
+                admin,
+            )
+            .unwrap()
+            .data
+            .address;
+
+        let rewards = RewardsWithAppAccess::new(&app);
+        rewards
+            .set_rewards_address(&contract_addr, admin, admin.address())
+            .unwrap();
+        rewards
+            .to_rewards()
+            .set_flat_fee(
+                MsgSetFlatFee {
+                    sender_address: admin.address(),
+                    contract_address: contract_addr.clone(),
+                    flat_fee_amount: Some(to_proto(&arch(1))),
+                },
+                admin,
+            )
+            .unwrap();
+
+        assert_eq!(
+            app.contract_flat_fee(&contract_addr).unwrap(),
+            vec![arch(1)]
+        );
+
+        #[cw_serde]
+        pub enum ExecMsg {
+            EmptyLoad {},
+        }
+
+        wasm.execute(&contract_addr, &ExecMsg::EmptyLoad {}, &[], user)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_gas_price_strategies() {
+        let mut app = ArchwayApp::default();
+        let accounts = app.init_accounts(&[arch(100)], 2).unwrap();
+        let sender = accounts.get(0).unwrap();
+        let receiver = accounts.get(1).unwrap();
+
+        let min_consensus_fee = app.min_consensus_fee().unwrap();
+        assert_eq!(min_consensus_fee.denom, "aarch");
+
+        let balance = |app: &ArchwayApp| {
+            Bank::new(app)
+                .balance(sender.address(), "aarch")
+                .unwrap()
+                .balance
+                .unwrap()
+                .amount
+                .parse::<u128>()
+                .unwrap()
+        };
+        // Sends 1 arch, asserting the fee charged is the gas price applied to the gas limit,
+        // floored at the estimation of the chain
+        let send_at = |app: &ArchwayApp, gas_price: Decimal256| {
+            let before = balance(app);
+            let res = Bank::new(app)
+                .send(
+                    MsgSend {
+                        from_address: sender.address(),
+                        to_address: receiver.address(),
+                        amount: vec![to_proto(&arch(1))],
+                    },
+                    sender,
+                )
+                .unwrap();
+
+            let gas_limit = res.gas_info.gas_wanted;
+            let gas_fee = Uint128::try_from(
+                (Decimal256::from_ratio(gas_limit, 1u128) * gas_price).to_uint_ceil(),
+            )
+            .unwrap()
+            .u128();
+            let floor = app
+                .estimate_tx_fees(gas_limit, None)
+                .unwrap()
+                .iter()
+                .find(|coin| coin.denom == "aarch")
+                .map(|coin| coin.amount.u128())
+                .unwrap_or_default();
+            let fee = gas_fee.max(floor);
+
+            assert_eq!(
+                fee_paid_by(&res.events, &sender.address(), "aarch").unwrap(),
+                fee
+            );
+            assert_eq!(before - balance(app), arch(1).amount.u128() + fee);
+            fee
+        };
+
+        app.set_gas_price_strategy(GasPriceStrategy::Fixed);
+        send_at(&app, Decimal256::from_ratio(DEFAULT_GAS_PRICE, 1u128));
+
+        app.set_gas_price_strategy(GasPriceStrategy::Queried);
+        send_at(&app, min_consensus_fee.amount);
+
+        let custom_price = Decimal256::from_str("900000000000.5").unwrap();
+        app.set_gas_price_strategy(GasPriceStrategy::Custom(DecCoin::new(
+            custom_price,
+            "aarch",
+        )));
+        let custom_fee = send_at(&app, custom_price);
+        assert!(custom_fee > 0);
+    }
+
+    #[test]
+    fn test_error_subscription() {
+        let app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/low_gas_demo.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, &admin)
+            .unwrap()
+            .data
+            .code_id;
+
+        #[derive(Serialize)]
+        struct InstMsg {}
+
+        let contract_addr = wasm
+            .instantiate(
+                code_id,
+                &InstMsg {},
+                Some(&admin.address()),
+                Some("test_contract"),
+                &[],
+                &admin,
+            )
+            .unwrap()
+            .data
+            .address;
+
+        let cwerrors = CwErrors::new(&app);
+        assert!(!cwerrors.is_subscribed(&contract_addr).unwrap());
+
+        cwerrors.subscribe_to_error(&contract_addr, &admin).unwrap();
+        assert!(cwerrors.is_subscribed(&contract_addr).unwrap());
+
+        assert!(cwerrors.contract_errors(&contract_addr).unwrap().is_empty());
+        assert!(cwerrors
+            .find_error(&contract_addr, ErrorModule::Callback)
+            .is_err());
+    }
+
+    /// Instantiates test_artifacts/sudo_recorder.wat, which records the sudo messages it receives
+    /// and fails the callbacks with an odd job id
+    fn instantiate_sudo_recorder(app: &ArchwayApp, admin: &SigningAccount) -> String {
+        let wasm = Wasm::new(app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/sudo_recorder.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, admin)
+            .unwrap()
+            .data
+            .code_id;
+
+        wasm.instantiate(
+            code_id,
+            &serde_json::json!({}),
+            Some(&admin.address()),
+            Some("sudo_recorder"),
+            &[],
+            admin,
+        )
+        .unwrap()
+        .data
+        .address
+    }
+
+    /// Sudo messages received by the sudo recorder, oldest first
+    fn recorded_sudo_msgs(app: &ArchwayApp, contract: &str) -> Vec<serde_json::Value> {
+        app.contract_state_snapshot(contract)
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|(key, _)| key.starts_with(b"sudo"))
+            .map(|(_, msg)| serde_json::from_slice(msg).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_run_until_callback() {
+        let app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+        let contract_addr = instantiate_sudo_recorder(&app, &admin);
+
+        let callbacks = CallbackWithAppAccess::new(&app);
+        let height = app.get_block_height().unwrap() + 5;
+        let callback = callbacks
+            .schedule_callback(&contract_addr, 2, height, None, &admin)
+            .unwrap();
+        assert_eq!(
+            callbacks.callback_outcome(&callback).unwrap(),
+            CallbackOutcome::Pending
+        );
+
+        assert_eq!(
+            callbacks.run_until_callback(&callback).unwrap(),
+            CallbackOutcome::Executed
+        );
+        assert_eq!(app.get_block_height().unwrap(), height);
+        assert_eq!(
+            recorded_sudo_msgs(&app, &contract_addr),
+            vec![serde_json::json!({"callback": {"job_id": 2}})]
+        );
+    }
+
+    #[test]
+    fn test_run_until_failing_callback() {
+        let app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+        let contract_addr = instantiate_sudo_recorder(&app, &admin);
+
+        let callbacks = CallbackWithAppAccess::new(&app);
+        let height = app.get_block_height().unwrap() + 5;
+        let callback = callbacks
+            .schedule_callback(&contract_addr, 1, height, None, &admin)
+            .unwrap();
+
+        let CallbackOutcome::Failed(error) = callbacks.run_until_callback(&callback).unwrap()
+        else {
+            panic!("the callback did not fail");
+        };
+        assert_eq!(error.module, ErrorModule::Callback);
+        assert_eq!(error.contract, contract_addr);
+        assert!(error.message.contains("odd job id"));
+        assert!(recorded_sudo_msgs(&app, &contract_addr).is_empty());
+    }
+
+    #[test]
+    fn test_failing_callback_error_received() {
+        let app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+        let contract_addr = instantiate_sudo_recorder(&app, &admin);
+
+        let cwerrors = CwErrors::new(&app);
+        cwerrors.subscribe_to_error(&contract_addr, &admin).unwrap();
+
+        let callbacks = CallbackWithAppAccess::new(&app);
+        let height = app.get_block_height().unwrap() + 5;
+        let callback = callbacks
+            .schedule_callback(&contract_addr, 1, height, None, &admin)
+            .unwrap();
+        assert!(matches!(
+            callbacks.run_until_callback(&callback).unwrap(),
+            CallbackOutcome::Failed(_)
+        ));
+        // Subscribed contracts get their errors at the end of a block, at the latest the next one
+        app.skip_block().unwrap();
+
+        let error = cwerrors
+            .find_error(&contract_addr, ErrorModule::Callback)
+            .unwrap();
+        assert_eq!(
+            error.payload::<serde_json::Value>().unwrap(),
+            serde_json::json!({"callback": {"job_id": 1}})
+        );
+
+        let sudo_msgs = recorded_sudo_msgs(&app, &contract_addr);
+        assert_eq!(sudo_msgs.len(), 1);
+        assert_eq!(sudo_msgs[0]["error"]["module_name"], "callback");
+        assert_eq!(
+            sudo_msgs[0]["error"]["error_message"],
+            error.message.as_str()
+        );
+    }
+
+    #[test]
+    fn test_execute_with_contract_granter() {
+        let app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+        let signer = app.init_account(&[arch(10)]).unwrap();
+        let granter = instantiate_sudo_recorder(&app, &admin);
+        app.fund(&granter, &[arch(10)]).unwrap();
+
+        // Contracts register themselves as granters
+        Wasm::new(&app)
+            .execute(
+                &granter,
+                &serde_json::json!({
+                    "messages": [stargate_submsg(&MsgRegisterAsGranter {
+                        granting_contract: granter.clone(),
+                    })]
+                }),
+                &[],
+                &admin,
+            )
+            .unwrap();
+
+        // The funds sent to the granter must not be mistaken for a refund of its fee
+        let res = app
+            .execute_with_contract_granter::<_, MsgExecuteContractResponse>(
+                &[(
+                    MsgExecuteContract {
+                        sender: signer.address(),
+                        contract: granter.clone(),
+                        msg: br#"{"messages":[]}"#.to_vec(),
+                        funds: vec![to_proto(&arch(1))],
+                    },
+                    MsgExecuteContract::type_url().as_str(),
+                )],
+                &signer,
+                &granter,
+            )
+            .unwrap();
+        assert!(res.granter_paid > 0);
+        assert_eq!(res.signer_paid, 0);
+
+        let balance = |address: &str| {
+            Bank::new(&app)
+                .balance(address, "aarch")
+                .unwrap()
+                .balance
+                .unwrap()
+                .amount
+                .parse::<u128>()
+                .unwrap()
+        };
+        assert_eq!(balance(&signer.address()), arch(9).amount.u128());
+        assert_eq!(balance(&granter), arch(11).amount.u128() - res.granter_paid);
+
+        // The granter approved the fee through sudo
+        let sudo_msgs = recorded_sudo_msgs(&app, &granter);
+        assert_eq!(sudo_msgs.len(), 1);
+        assert!(sudo_msgs[0]["cw_grant"].is_object());
+    }
+
+    #[test]
+    fn test_mint_to() {
+        let app = ArchwayApp::default();
+        let account = app.init_account(&[arch(1)]).unwrap();
+
+        app.mint_to(&account.address(), &coins(500, "ucustom"))
+            .unwrap();
+
+        let bank = Bank::new(&app);
+        let balance = bank
+            .balance(account.address(), "ucustom")
+            .unwrap()
+            .balance
+            .unwrap();
+        assert_eq!(balance.amount, "500");
+
+        let metadata = bank
+            .query_denom_metadata(&QueryDenomMetadataRequest {
+                denom: "ucustom".to_string(),
+            })
+            .unwrap()
+            .metadata
+            .unwrap();
+        assert_eq!(metadata.base, "ucustom");
+
+        assert!(app.mint_to("not-an-address", &coins(1, "ucustom")).is_err());
+    }
+
+    #[test]
+    fn test_fund() {
+        let app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/cw1_whitelist.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, &admin)
+            .unwrap()
+            .data
+            .code_id;
+        let contract_addr = wasm
+            .instantiate(
+                code_id,
+                &cw1_whitelist::msg::InstantiateMsg {
+                    admins: vec![admin.address()],
+                    mutable: false,
+                },
+                Some(&admin.address()),
+                Some("whitelist"),
+                &[],
+                &admin,
+            )
+            .unwrap()
+            .data
+            .address;
+
+        app.fund(&contract_addr, &[arch(5)]).unwrap();
+
+        let bank = Bank::new(&app);
+        let balance = bank
+            .balance(&contract_addr, "aarch")
+            .unwrap()
+            .balance
+            .unwrap();
+        assert_eq!(balance.amount, arch(5).amount.to_string());
+
+        let distribution = "cosmos1jv65s3grqf6v6jl3dp4t6c9t9rk99cd88lyufl";
+        app.fund_module("distribution", &[arch(1)]).unwrap();
+        let balance = bank
+            .balance(distribution, "aarch")
+            .unwrap()
+            .balance
+            .unwrap();
+        assert!(balance.amount.parse::<u128>().unwrap() >= arch(1).amount.u128());
+    }
+
+    #[test]
+    fn test_fee_settings() {
+        let mut app = ArchwayApp::default();
+        app.set_default_gas_adjustment(2.0);
+        app.set_default_gas_price(DEFAULT_GAS_PRICE * 2);
+        assert_eq!(
+            app.default_gas_price(),
+            Coin::new(DEFAULT_GAS_PRICE * 2, "aarch")
+        );
+
+        let accounts = app.init_accounts(&[arch(100)], 2).unwrap();
+        let sender = &accounts[0];
+        let receiver = &accounts[1];
+        assert!(matches!(
+            sender.fee_setting(),
+            FeeSetting::Auto { gas_adjustment, .. } if *gas_adjustment == 2.0
+        ));
+
+        let send = |fee_setting: &FeeSetting| {
+            app.execute_multiple_with_fee_setting::<_, MsgSendResponse>(
+                &[(
+                    MsgSend {
+                        from_address: sender.address(),
+                        to_address: receiver.address(),
+                        amount: vec![to_proto(&arch(1))],
+                    },
+                    MsgSend::type_url().as_str(),
+                )],
+                sender,
+                None,
+                fee_setting,
+            )
+            .unwrap()
+            .gas_info
+        };
+
+        let res = send(&FeeSetting::Custom {
+            amount: arch(1),
+            gas_limit: 500_000,
+        });
+        assert_eq!(res.gas_wanted, 500_000);
+
+        let mut previous = 0;
+        for gas_adjustment in [1.2, 1.5, 3.0] {
+            let res = send(&FeeSetting::Auto {
+                gas_price: app.default_gas_price(),
+                gas_adjustment,
+            });
+            assert!(res.gas_wanted > previous);
+            previous = res.gas_wanted;
+        }
+    }
+
+    #[test]
+    fn test_gas_breakdown() {
+        let mut app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/cw1_whitelist.wasm").unwrap();
+        let code_id = wasm
+            .store_code(&wasm_byte_code, None, &admin)
+            .unwrap()
+            .data
+            .code_id;
+        let instantiate = |app: &ArchwayApp| {
+            Wasm::new(app)
+                .instantiate(
+                    code_id,
+                    &cw1_whitelist::msg::InstantiateMsg {
+                        admins: vec![admin.address()],
+                        mutable: false,
+                    },
+                    Some(&admin.address()),
+                    Some("whitelist"),
+                    &[],
+                    &admin,
+                )
+                .unwrap()
+        };
+
+        let res = instantiate(&app);
+        assert_eq!(GasBreakdown::from_events(&res.events).unwrap(), None);
+
+        app.set_gas_tracing(true).unwrap();
+        let res = instantiate(&app);
+        let breakdown = GasBreakdown::from_events(&res.events).unwrap().unwrap();
+        assert_eq!(breakdown.total, res.gas_info.gas_used);
+        assert!(breakdown.ante.total > 0);
+        assert!(breakdown.wasm.compute > 0);
+        assert!(breakdown.storage.writes > 0);
+        assert!(breakdown.storage.write_bytes > 0);
+        assert!(breakdown.storage.total() > 0);
+    }
+
+    #[test]
+    fn test_storage_trace() {
+        let mut app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/cw1_whitelist.wasm").unwrap();
+        let res = wasm.store_code(&wasm_byte_code, None, &admin).unwrap();
+        assert_eq!(StorageAccess::from_events(&res.events).unwrap(), None);
+
+        app.set_storage_tracing(true).unwrap();
+        let res = Wasm::new(&app)
+            .instantiate(
+                res.data.code_id,
+                &cw1_whitelist::msg::InstantiateMsg {
+                    admins: vec![admin.address()],
+                    mutable: false,
+                },
+                Some(&admin.address()),
+                Some("whitelist"),
+                &[],
+                &admin,
+            )
+            .unwrap();
+        let contract = res.data.address;
+
+        let accesses = StorageAccess::from_events(&res.events).unwrap().unwrap();
+        assert!(accesses.iter().any(|access| access.ante));
+        assert!(accesses.iter().all(|access| access.gas > 0));
+
+        let contract_writes: Vec<_> = accesses
+            .iter()
+            .filter(|access| {
+                access.operation == StorageOperation::Write
+                    && access.contract.as_deref() == Some(contract.as_str())
+            })
+            .collect();
+        assert!(!contract_writes.is_empty());
+        assert!(contract_writes.iter().all(|access| !access.ante
+            && access.store == "wasm"
+            && access.value_size > 0
+            && access.contract_key.is_some()));
+    }
+
+    #[test]
+    fn test_contract_debug_capture() {
+        let mut app = ArchwayApp::default();
+        let admin = app.init_account(&[arch(100)]).unwrap();
+
+        let wasm = Wasm::new(&app);
+        let wasm_byte_code = std::fs::read("./test_artifacts/sudo_recorder.wasm").unwrap();
+        let res = wasm.store_code(&wasm_byte_code, None, &admin).unwrap();
+        assert_eq!(DebugMessage::from_events(&res.events).unwrap(), None);
+
+        app.set_contract_debug_capture(true).unwrap();
+        let res = Wasm::new(&app)
+            .instantiate(
+                res.data.code_id,
+                &serde_json::json!({}),
+                Some(&admin.address()),
+                Some("sudo_recorder"),
+                &[],
+                &admin,
+            )
+            .unwrap();
+
+        let messages = DebugMessage::from_events(&res.events).unwrap().unwrap();
+        assert_eq!(
+            messages
+                .iter()
+                .map(|message| message.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["sudo recorder instantiated"]
+        );
+        assert!(messages[0].gas_remaining > 0);
+
+        // Executions that print nothing still get the event
+        let res = Wasm::new(&app)
+            .execute(
+                &res.data.address,
+                &serde_json::json!({ "messages": [] }),
+                &[],
+                &admin,
+            )
+            .unwrap();
+        assert_eq!(
+            DebugMessage::from_events(&res.events).unwrap(),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let app = ArchwayApp::default();
+        let names = app.upgrade_names().unwrap();
+        let latest = names.last().unwrap();
+
+        let height = app.get_block_height().unwrap() + 10;
+        assert!(app.schedule_upgrade("litmus-unknown", height).is_err());
+
+        app.schedule_upgrade(latest, height).unwrap();
+        assert!(app.apply_upgrade("litmus-unknown").is_err());
+        assert_eq!(app.apply_upgrade(latest).unwrap(), height);
+        assert_eq!(app.get_block_height().unwrap(), height);
+
+        let applied = Upgrade::new(&app)
+            .query_applied_plan(&QueryAppliedPlanRequest {
+                name: latest.clone(),
+            })
+            .unwrap();
+        assert_eq!(applied.height, height);
+
+        // The chain keeps going after the upgrade
+        app.skip_block().unwrap();
+    }
+
+    #[test]
+    fn test_simulate() {
+        let app = ArchwayApp::default();
+        let accounts = app.init_accounts(&[arch(100)], 2).unwrap();
+        let sender = &accounts[0];
+        let receiver = &accounts[1];
+
+        let msgs = [(
+            MsgSend {
+                from_address: sender.address(),
+                to_address: receiver.address(),
+                amount: vec![to_proto(&arch(1))],
+            },
+            MsgSend::type_url(),
+        )];
+        let msgs: Vec<_> = msgs
+            .iter()
+            .map(|(msg, type_url)| (msg.clone(), type_url.as_str()))
+            .collect();
+
+        let height = app.get_block_height().unwrap();
+        let bank = Bank::new(&app);
+        let balance = |address: String| bank.balance(address, "aarch").unwrap().balance;
+        let sender_balance = balance(sender.address());
+
+        let simulation = app.simulate::<_, MsgSendResponse>(&msgs, sender).unwrap();
+        assert!(simulation.response.gas_info.gas_used > 0);
+        assert_eq!(
+            simulation.response.gas_info.gas_wanted,
+            simulation.fee.gas_limit
+        );
+        assert!(simulation
+            .response
+            .events
+            .iter()
+            .any(|event| event.ty == "transfer"));
+
+        // Nothing is committed
+        assert_eq!(app.get_block_height().unwrap(), height);
+        assert_eq!(balance(sender.address()), sender_balance);
+
+        let res = app
+            .execute_multiple_with_granter::<_, MsgSendResponse>(&msgs, sender, None)
+            .unwrap();
+        assert_eq!(res.gas_info.gas_wanted, simulation.fee.gas_limit);
+    }
+
+    #[test]
+    fn test_impersonate() {
+        let app = ArchwayApp::default();
+        let receiver = app.init_account(&[arch(1)]).unwrap();
+
+        let address = app.init_address(&[arch(3)]).unwrap();
+        assert_ne!(address, app.init_address(&[]).unwrap());
+
+        let bank = Bank::new(&app);
+        let balance = |address: &str| {
+            bank.balance(address, "aarch")
+                .unwrap()
+                .balance
+                .unwrap()
+                .amount
+                .parse::<u128>()
+                .unwrap()
+        };
+
+        let signer = app.impersonate(&address);
+        let height = app.get_block_height().unwrap();
+        let res = app
+            .execute_multiple_as::<_, MsgSendResponse>(
+                &[(
+                    MsgSend {
+                        from_address: address.clone(),
+                        to_address: receiver.address(),
+                        amount: vec![to_proto(&arch(1))],
+                    },
+                    MsgSend::type_url().as_str(),
+                )],
+                &signer,
+            )
+            .unwrap();
+
+        // The tx runs in its own block like any other tx
+        assert_eq!(app.get_block_height().unwrap(), height + 1);
+        assert!(res.events.iter().any(|event| event.ty == "transfer"));
+        assert!(res.gas_info.gas_used > 0);
+
+        // No fees are paid by impersonated signers
+        assert_eq!(balance(&address), arch(2).amount.u128());
+        assert_eq!(balance(&receiver.address()), arch(2).amount.u128());
+
+        // Messages of other signers are rejected
+        let err = app
+            .execute_multiple_as::<_, MsgSendResponse>(
+                &[(
+                    MsgSend {
+                        from_address: receiver.address(),
+                        to_address: address.clone(),
+                        amount: vec![to_proto(&arch(1))],
+                    },
+                    MsgSend::type_url().as_str(),
+                )],
+                &signer,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("must be signed by"));
+        assert_eq!(balance(&address), arch(2).amount.u128());
+    }
+
+    #[test]
+    fn test_ibc_relayer() {
+        let controller = ArchwayApp::default();
+        let host = ArchwayApp::new_with_chain_id("archway-2");
+        assert_eq!(host.chain_id(), "archway-2");
+
+        let relayer = Relayer::new(&controller, &host).unwrap();
+        let path = relayer.path();
+        assert_eq!(path.client_a, "07-tendermint-0");
+        assert_eq!(path.client_b, "07-tendermint-0");
+        assert_eq!(path.connection_a, "connection-0");
+        assert_eq!(path.connection_b, "connection-0");
+
+        // Nothing was sent through the connection yet
+        assert_eq!(relayer.relay().unwrap(), RelayReport::default());
+
+        // Chains keep working after relaying
+        let account = host.init_account(&[arch(1)]).unwrap();
+        let balance = Bank::new(&host)
+            .balance(account.address(), "aarch")
+            .unwrap()
+            .balance
+            .unwrap();
+        assert_eq!(balance.amount, arch(1).amount.to_string());
+    }
+
+    #[test]
+    fn test_ibc_transfer() {
+        let chain_a = ArchwayApp::default();
+        let chain_b = ArchwayApp::new_with_chain_id("archway-2");
+        let sender = chain_a.init_account(&[arch(10)]).unwrap();
+        let receiver = chain_b.init_account(&[arch(10)]).unwrap();
+
+        let coordinator = IbcCoordinator::new(&chain_a, &chain_b).unwrap();
+        let channel = coordinator.create_transfer_channel().unwrap();
+        assert_eq!(channel.channel_a, "channel-0");
+        assert_eq!(channel.channel_b, "channel-0");
+
+        let trace = coordinator
+            .transfer_a_to_b(&channel, &arch(1), &sender, &receiver.address())
+            .unwrap();
+        assert_eq!(trace.path, "transfer/channel-0");
+        assert_eq!(trace.base_denom, "aarch");
+        assert_eq!(
+            Transfer::new(&chain_b)
+                .denom_trace(&trace.ibc_denom())
+                .unwrap(),
+            trace
+        );
+
+        let balance = Bank::new(&chain_b)
+            .balance(receiver.address(), trace.ibc_denom())
+            .unwrap()
+            .balance
+            .unwrap();
+        assert_eq!(balance.amount, arch(1).amount.to_string());
+
+        // Sending the voucher back unwinds the trace
+        let voucher = Coin::new(arch(1).amount, trace.ibc_denom());
+        let trace = coordinator
+            .transfer_b_to_a(&channel, &voucher, &receiver, &sender.address())
+            .unwrap();
+        assert_eq!(trace.ibc_denom(), "aarch");
+        assert_eq!(coordinator.relay_all().unwrap(), RelayReport::default());
+    }
+
+    /// Submessage making the sudo recorder send the message as itself
+    fn stargate_submsg<M: Message + Name>(msg: &M) -> serde_json::Value {
+        serde_json::json!({
+            "id": 0,
+            "msg": {
+                "stargate": {
+                    "type_url": M::type_url(),
+                    "value": BASE64_STANDARD.encode(msg.encode_to_vec()),
+                }
+            },
+            "gas_limit": null,
+            "reply_on": "never",
+        })
+    }
+
+    /// Registers an interchain account of a sudo recorder on the host chain, returning the
+    /// contract and the account address on the host
+    fn register_interchain_account(relayer: &Relayer, admin: &SigningAccount) -> (String, String) {
+        let controller = relayer.chain_a();
+        let contract_addr = instantiate_sudo_recorder(controller, admin);
+
+        Wasm::new(controller)
+            .execute(
+                &contract_addr,
+                &serde_json::json!({
+                    "messages": [stargate_submsg(&MsgRegisterInterchainAccount {
+                        contract_address: contract_addr.clone(),
+                        connection_id: relayer.path().connection_a.clone(),
+                    })]
+                }),
+                &[],
+                admin,
+            )
+            .unwrap();
+        assert_eq!(relayer.relay().unwrap().handshakes, 3);
+
+        let sudo_msgs = recorded_sudo_msgs(controller, &contract_addr);
+        assert_eq!(sudo_msgs.len(), 1);
+        let ica_address = sudo_msgs[0]["ica"]["account_registered"]["counterparty_address"]
+            .as_str()
+            .expect("the account registration was not received")
+            .to_string();
+
+        (contract_addr, ica_address)
+    }
+
+    /// Makes the sudo recorder send a bank transfer from its interchain account
+    fn send_from_interchain_account(
+        relayer: &Relayer,
+        contract_addr: &str,
+        ica_address: &str,
+        receiver: &str,
+        timeout_seconds: u64,
+        admin: &SigningAccount,
+    ) {
+        let send = MsgSend {
+            from_address: ica_address.to_string(),
+            to_address: receiver.to_string(),
+            amount: vec![to_proto(&arch(1))],
+        };
+
+        Wasm::new(relayer.chain_a())
+            .execute(
+                contract_addr,
+                &serde_json::json!({
+                    "messages": [stargate_submsg(&MsgSendTx {
+                        contract_address: contract_addr.to_string(),
+                        connection_id: relayer.path().connection_a.clone(),
+                        msgs: vec![ProtoAny {
+                            type_url: MsgSend::type_url(),
+                            value: send.encode_to_vec(),
+                        }],
+                        memo: String::new(),
+                        timeout: timeout_seconds,
+                    })]
+                }),
+                &[],
+                admin,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_interchain_account_tx() {
+        let controller = ArchwayApp::default();
+        let host = ArchwayApp::new_with_chain_id("archway-2");
+        let admin = controller.init_account(&[arch(100)]).unwrap();
+        let receiver = host.init_account(&[arch(1)]).unwrap();
+
+        let relayer = Relayer::new(&controller, &host).unwrap();
+        let (contract_addr, ica_address) = register_interchain_account(&relayer, &admin);
+        host.fund(&ica_address, &[arch(10)]).unwrap();
+
+        send_from_interchain_account(
+            &relayer,
+            &contract_addr,
+            &ica_address,
+            &receiver.address(),
+            600,
+            &admin,
+        );
+        let report = relayer.relay().unwrap();
+        assert_eq!(report.packets, 1);
+        assert_eq!(report.acks, 1);
+        assert_eq!(report.timeouts, 0);
+
+        let balance = Bank::new(&host)
+            .balance(receiver.address(), "aarch")
+            .unwrap()
+            .balance
+            .unwrap();
+        assert_eq!(balance.amount, arch(2).amount.to_string());
+
+        // The ack reached the contract through sudo
+        let sudo_msgs = recorded_sudo_msgs(&controller, &contract_addr);
+        assert_eq!(sudo_msgs.len(), 2);
+        assert!(sudo_msgs[1]["ica"]["tx_executed"].is_object());
+    }
+
+    #[test]
+    fn test_interchain_account_timeout() {
+        let controller = ArchwayApp::default();
+        let host = ArchwayApp::new_with_chain_id("archway-2");
+        let admin = controller.init_account(&[arch(100)]).unwrap();
+        let receiver = host.init_account(&[arch(1)]).unwrap();
+
+        let relayer = Relayer::new(&controller, &host).unwrap();
+        let (contract_addr, ica_address) = register_interchain_account(&relayer, &admin);
+        host.fund(&ica_address, &[arch(10)]).unwrap();
+        CwErrors::new(&controller)
+            .subscribe_to_error(&contract_addr, &admin)
+            .unwrap();
+
+        send_from_interchain_account(
+            &relayer,
+            &contract_addr,
+            &ica_address,
+            &receiver.address(),
+            60,
+            &admin,
+        );
+        // The host moves past the timeout before the packet is relayed
+        host.increase_time(600).unwrap();
+        let report = relayer.relay().unwrap();
+        assert_eq!(report.packets, 0);
+        assert_eq!(report.timeouts, 1);
+        controller.skip_block().unwrap();
+
+        let balance = Bank::new(&host)
+            .balance(receiver.address(), "aarch")
+            .unwrap()
+            .balance
+            .unwrap();
+        assert_eq!(balance.amount, arch(1).amount.to_string());
+
+        // cwica reports timeouts as errors, which subscribed contracts receive through sudo
+        let error = CwErrors::new(&controller)
+            .find_error(&contract_addr, ErrorModule::CwIca)
             .unwrap();
+        assert_eq!(error.contract, contract_addr);
+        let sudo_msgs = recorded_sudo_msgs(&controller, &contract_addr);
+        assert_eq!(sudo_msgs.len(), 2);
+        assert_eq!(sudo_msgs[1]["error"]["module_name"], "cwica");
     }
 
     #[test]
     fn test_block_skipping() {
         let app = ArchwayApp::default();
 
-        assert_eq!(app.get_block_height(), 1i64);
+        assert_eq!(app.get_block_height().unwrap(), 1i64);
 
-        app.skip_block();
+        app.skip_block().unwrap();
 
-        assert_eq!(app.get_block_height(), 2i64);
+        assert_eq!(app.get_block_height().unwrap(), 2i64);
 
-        app.skip_blocks(5);
+        app.skip_blocks(5).unwrap();
 
-        assert_eq!(app.get_block_height(), 7i64);
+        assert_eq!(app.get_block_height().unwrap(), 7i64);
     }
 
     #[test]
@@ -725,7 +3248,7 @@ mod tests {
             .unwrap();
         assert_eq!(res, delegation.amount.u128());
 
-        app.skip_blocks(1000);
+        app.skip_blocks(1000).unwrap();
 
         let distribution = Distribution::new(&app);
         let res = distribution
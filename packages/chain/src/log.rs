@@ -0,0 +1,92 @@
+use crate::bindings::SetLogSink;
+use crate::json_event_attribute;
+use cosmwasm_std::Event;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use test_tube::runner::result::RawResult;
+use test_tube::{redefine_as_go_string, RunnerResult};
+
+/// Event attached to tx results once contract debug capture is enabled with
+/// [`crate::ArchwayApp::set_contract_debug_capture`]
+pub const CONTRACT_DEBUG_EVENT: &str = "litmus_contract_debug";
+const MESSAGES_ATTRIBUTE: &str = "messages";
+
+/// Where the chain logs of the apps go
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum LogSink {
+    #[default]
+    Discard,
+    Stdout,
+    Stderr,
+    /// Appends the logs to the file, creating it if needed
+    File(PathBuf),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+/// Sets where the apps created afterwards write their logs, they are discarded by default.
+/// The sink is shared by the whole process, apps created before keep their sink
+pub fn set_log_sink(sink: &LogSink, level: LogLevel) -> RunnerResult<()> {
+    let sink = match sink {
+        LogSink::Discard => "discard".to_string(),
+        LogSink::Stdout => "stdout".to_string(),
+        LogSink::Stderr => "stderr".to_string(),
+        LogSink::File(path) => path.to_string_lossy().into_owned(),
+    };
+    let level = level.as_str();
+    redefine_as_go_string!(sink);
+    redefine_as_go_string!(level);
+
+    unsafe { RawResult::from_non_null_ptr(SetLogSink(sink, level)).into_result()? };
+    Ok(())
+}
+
+/// Chain log entry captured with [`crate::ArchwayApp::set_log_capture`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Missing for entries logged without a level
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Module that logged the entry, e.g. `x/wasm`
+    #[serde(default)]
+    pub module: Option<String>,
+    #[serde(default)]
+    pub message: String,
+    /// Remaining key values of the entry, including its time
+    #[serde(flatten)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Message printed by a contract with `deps.api.debug`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugMessage {
+    pub message: String,
+    pub gas_remaining: u64,
+}
+
+impl DebugMessage {
+    /// Reads the messages from the events of an execution, they are only there when contract debug capture is enabled
+    pub fn from_events(events: &[Event]) -> RunnerResult<Option<Vec<Self>>> {
+        json_event_attribute(events, CONTRACT_DEBUG_EVENT, MESSAGES_ATTRIBUTE)
+    }
+}
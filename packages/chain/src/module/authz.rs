@@ -1,15 +1,24 @@
 use archway_proto::cosmos::authz::v1beta1::{
-    GenericAuthorization, Grant, MsgExec, MsgExecResponse, MsgGrant, MsgGrantResponse,
+    GenericAuthorization, Grant, MsgExec, MsgExecResponse, MsgGrant, MsgGrantResponse, MsgRevoke,
+    MsgRevokeResponse, QueryGranteeGrantsRequest, QueryGranteeGrantsResponse,
+    QueryGranterGrantsRequest, QueryGranterGrantsResponse, QueryGrantsRequest, QueryGrantsResponse,
 };
-use archway_proto::cosmos::bank::v1beta1::SendAuthorization;
+use archway_proto::cosmos::bank::v1beta1::{MsgSend, SendAuthorization};
+use archway_proto::cosmos::base::query::v1beta1::PageRequest;
 use archway_proto::cosmos::staking::v1beta1::stake_authorization::Policy;
-use archway_proto::cosmos::staking::v1beta1::{AuthorizationType, StakeAuthorization};
+use archway_proto::cosmos::staking::v1beta1::{AuthorizationType, MsgDelegate, StakeAuthorization};
+use archway_proto::cosmwasm::wasm::v1::{
+    AcceptedMessageKeysFilter, AcceptedMessagesFilter, AllowAllMessagesFilter, CombinedLimit,
+    ContractExecutionAuthorization, ContractGrant, ContractMigrationAuthorization, MaxCallsLimit,
+    MaxFundsLimit, MsgExecuteContract,
+};
 use archway_proto::tendermint::google::protobuf::{Any, Timestamp};
 use cosmwasm_std::Coin;
 use prost::Name;
 use test_tube::cosmrs::tx::MessageExt;
 use test_tube::{
-    fn_execute, Account, Module, Runner, RunnerError, RunnerExecuteResult, SigningAccount,
+    fn_execute, fn_query, Account, Module, Runner, RunnerError, RunnerExecuteResult, RunnerResult,
+    SigningAccount,
 };
 
 pub struct Authz<'a, R: Runner<'a>> {
@@ -34,6 +43,22 @@ where
         pub _grant: MsgGrant["/cosmos.authz.v1beta1.MsgGrant"] => MsgGrantResponse
     }
 
+    fn_execute! {
+        pub _revoke: MsgRevoke["/cosmos.authz.v1beta1.MsgRevoke"] => MsgRevokeResponse
+    }
+
+    fn_query! {
+        pub query_grants ["/cosmos.authz.v1beta1.Query/Grants"]: QueryGrantsRequest => QueryGrantsResponse
+    }
+
+    fn_query! {
+        pub query_granter_grants ["/cosmos.authz.v1beta1.Query/GranterGrants"]: QueryGranterGrantsRequest => QueryGranterGrantsResponse
+    }
+
+    fn_query! {
+        pub query_grantee_grants ["/cosmos.authz.v1beta1.Query/GranteeGrants"]: QueryGranteeGrantsRequest => QueryGranteeGrantsResponse
+    }
+
     pub fn grant<T>(
         &self,
         signer: &SigningAccount,
@@ -49,12 +74,7 @@ where
             granter,
             grantee: grantee.into(),
             grant: Some(Grant {
-                authorization: Some(Any {
-                    type_url: T::type_url(),
-                    value: msg
-                        .to_bytes()
-                        .map_err(|e| RunnerError::EncodeError(e.into()))?,
-                }),
+                authorization: Some(to_any(msg)?),
                 expiration,
             }),
         };
@@ -117,6 +137,220 @@ where
             expiration,
         )
     }
+
+    /// Grants the execution of the given contracts, see [`contract_grant`] to build each grant
+    pub fn grant_contract_execution(
+        &self,
+        signer: &SigningAccount,
+        grantee: impl Into<String>,
+        grants: Vec<ContractGrant>,
+        expiration: Option<Timestamp>,
+    ) -> RunnerExecuteResult<MsgGrantResponse> {
+        self.grant(
+            signer,
+            grantee,
+            ContractExecutionAuthorization { grants },
+            expiration,
+        )
+    }
+
+    /// Grants the migration of the given contracts, see [`contract_grant`] to build each grant
+    pub fn grant_contract_migration(
+        &self,
+        signer: &SigningAccount,
+        grantee: impl Into<String>,
+        grants: Vec<ContractGrant>,
+        expiration: Option<Timestamp>,
+    ) -> RunnerExecuteResult<MsgGrantResponse> {
+        self.grant(
+            signer,
+            grantee,
+            ContractMigrationAuthorization { grants },
+            expiration,
+        )
+    }
+
+    /// Executes the messages on behalf of their granters, signed by the grantee
+    pub fn exec_msgs(
+        &self,
+        signer: &SigningAccount,
+        msgs: Vec<AuthzMsg>,
+    ) -> RunnerExecuteResult<MsgExecResponse> {
+        let msgs = msgs
+            .into_iter()
+            .map(AuthzMsg::into_any)
+            .collect::<Result<Vec<Any>, RunnerError>>()?;
+
+        self.exec(
+            MsgExec {
+                grantee: signer.address(),
+                msgs,
+            },
+            signer,
+        )
+    }
+
+    /// Revokes the grant of the signer to the grantee for the given message type. Grants are keyed
+    /// by the type url of the authorized message, e.g. `MsgSend::type_url()` for
+    /// [`Authz::grant_send`], not by the type url of the authorization.
+    pub fn revoke(
+        &self,
+        signer: &SigningAccount,
+        grantee: impl Into<String>,
+        msg_type_url: impl Into<String>,
+    ) -> RunnerExecuteResult<MsgRevokeResponse> {
+        self._revoke(
+            MsgRevoke {
+                granter: signer.address(),
+                grantee: grantee.into(),
+                msg_type_url: msg_type_url.into(),
+            },
+            signer,
+        )
+    }
+
+    /// Queries the grants between a granter and grantee, optionally filtered by message type
+    pub fn grants(
+        &self,
+        granter: impl Into<String>,
+        grantee: impl Into<String>,
+        msg_type_url: Option<String>,
+        pagination: Option<PageRequest>,
+    ) -> RunnerResult<QueryGrantsResponse> {
+        self.query_grants(&QueryGrantsRequest {
+            granter: granter.into(),
+            grantee: grantee.into(),
+            msg_type_url: msg_type_url.unwrap_or_default(),
+            pagination,
+        })
+    }
+
+    pub fn granter_grants(
+        &self,
+        granter: impl Into<String>,
+        pagination: Option<PageRequest>,
+    ) -> RunnerResult<QueryGranterGrantsResponse> {
+        self.query_granter_grants(&QueryGranterGrantsRequest {
+            granter: granter.into(),
+            pagination,
+        })
+    }
+
+    pub fn grantee_grants(
+        &self,
+        grantee: impl Into<String>,
+        pagination: Option<PageRequest>,
+    ) -> RunnerResult<QueryGranteeGrantsResponse> {
+        self.query_grantee_grants(&QueryGranteeGrantsRequest {
+            grantee: grantee.into(),
+            pagination,
+        })
+    }
+}
+
+/// Messages that can be wrapped into a `MsgExec`
+pub enum AuthzMsg {
+    WasmExecute(MsgExecuteContract),
+    BankSend(MsgSend),
+    Delegate(MsgDelegate),
+    /// Any other message that is already encoded
+    Raw(Any),
+}
+
+impl AuthzMsg {
+    pub fn into_any(self) -> Result<Any, RunnerError> {
+        match self {
+            AuthzMsg::WasmExecute(msg) => to_any(msg),
+            AuthzMsg::BankSend(msg) => to_any(msg),
+            AuthzMsg::Delegate(msg) => to_any(msg),
+            AuthzMsg::Raw(any) => Ok(any),
+        }
+    }
+}
+
+impl From<MsgExecuteContract> for AuthzMsg {
+    fn from(msg: MsgExecuteContract) -> Self {
+        AuthzMsg::WasmExecute(msg)
+    }
+}
+
+impl From<MsgSend> for AuthzMsg {
+    fn from(msg: MsgSend) -> Self {
+        AuthzMsg::BankSend(msg)
+    }
+}
+
+impl From<MsgDelegate> for AuthzMsg {
+    fn from(msg: MsgDelegate) -> Self {
+        AuthzMsg::Delegate(msg)
+    }
+}
+
+impl From<Any> for AuthzMsg {
+    fn from(any: Any) -> Self {
+        AuthzMsg::Raw(any)
+    }
+}
+
+/// Limit applied to a contract grant
+pub enum GrantLimit {
+    MaxCalls(u64),
+    MaxFunds(Vec<Coin>),
+    Combined {
+        calls_remaining: u64,
+        amounts: Vec<Coin>,
+    },
+}
+
+/// Filter applied to the messages allowed by a contract grant
+pub enum GrantFilter {
+    AllowAll,
+    AcceptedKeys(Vec<String>),
+    AcceptedMessages(Vec<Vec<u8>>),
+}
+
+pub fn contract_grant(
+    contract: impl Into<String>,
+    limit: GrantLimit,
+    filter: GrantFilter,
+) -> Result<ContractGrant, RunnerError> {
+    let limit = match limit {
+        GrantLimit::MaxCalls(remaining) => to_any(MaxCallsLimit { remaining }),
+        GrantLimit::MaxFunds(amounts) => to_any(MaxFundsLimit {
+            amounts: amounts.iter().map(coin_to_proto_coin).collect(),
+        }),
+        GrantLimit::Combined {
+            calls_remaining,
+            amounts,
+        } => to_any(CombinedLimit {
+            calls_remaining,
+            amounts: amounts.iter().map(coin_to_proto_coin).collect(),
+        }),
+    }?;
+
+    let filter = match filter {
+        GrantFilter::AllowAll => to_any(AllowAllMessagesFilter {}),
+        GrantFilter::AcceptedKeys(keys) => to_any(AcceptedMessageKeysFilter { keys }),
+        GrantFilter::AcceptedMessages(messages) => to_any(AcceptedMessagesFilter { messages }),
+    }?;
+
+    Ok(ContractGrant {
+        contract: contract.into(),
+        limit: Some(limit),
+        filter: Some(filter),
+    })
+}
+
+fn to_any<T>(msg: T) -> Result<Any, RunnerError>
+where
+    T: Name + MessageExt,
+{
+    Ok(Any {
+        type_url: T::type_url(),
+        value: msg
+            .to_bytes()
+            .map_err(|e| RunnerError::EncodeError(e.into()))?,
+    })
 }
 
 fn coin_to_proto_coin(coin: &Coin) -> archway_proto::cosmos::base::v1beta1::Coin {
@@ -1,9 +1,14 @@
+use crate::module::{ContractError, CwErrors, ErrorModule};
+use crate::{to_proto, try_from_proto, ArchwayApp};
 use archway_proto::archway::callback::v1::{
     MsgCancelCallback, MsgCancelCallbackResponse, MsgRequestCallback, MsgRequestCallbackResponse,
     QueryCallbacksRequest, QueryCallbacksResponse, QueryEstimateCallbackFeesRequest,
     QueryEstimateCallbackFeesResponse, QueryParamsRequest, QueryParamsResponse,
 };
-use test_tube::{fn_execute, fn_query, Module, Runner};
+use cosmwasm_std::Coin;
+use test_tube::{
+    fn_execute, fn_query, Account, Module, Runner, RunnerError, RunnerResult, SigningAccount,
+};
 
 pub struct Callback<'a, R: Runner<'a>> {
     runner: &'a R,
@@ -38,4 +43,164 @@ where
     fn_query! {
         pub query_params ["/archway.callback.v1.Query/Params"]: QueryParamsRequest => QueryParamsResponse
     }
+
+    /// Queries the total fees required to schedule a callback at the given height
+    pub fn estimate_callback_fees(&self, height: i64) -> RunnerResult<Coin> {
+        self.query_estimate_callback_fees(&QueryEstimateCallbackFeesRequest {
+            block_height: height,
+        })?
+        .total_fees
+        .map(|fees| try_from_proto(&fees))
+        .transpose()?
+        .ok_or_else(|| RunnerError::QueryError {
+            msg: format!("no callback fees estimated for height {}", height),
+        })
+    }
+
+    /// Requests a callback for the contract, when no fees are given the estimated fees are paid.
+    /// The signer must be the contract admin, its metadata owner or the contract itself
+    pub fn schedule_callback(
+        &self,
+        contract: impl Into<String>,
+        job_id: u64,
+        height: i64,
+        fees: Option<Coin>,
+        signer: &SigningAccount,
+    ) -> RunnerResult<ScheduledCallback> {
+        let fees = match fees {
+            Some(fees) => fees,
+            None => self.estimate_callback_fees(height)?,
+        };
+        let contract = contract.into();
+
+        self.request_callback(
+            MsgRequestCallback {
+                sender: signer.address(),
+                job_id,
+                callback_height: height,
+                contract_address: contract.clone(),
+                fees: Some(to_proto(&fees)),
+            },
+            signer,
+        )?;
+
+        Ok(ScheduledCallback {
+            contract,
+            job_id,
+            height,
+            fees,
+        })
+    }
+}
+
+/// Callback requested through [`Callback::schedule_callback`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledCallback {
+    pub contract: String,
+    pub job_id: u64,
+    pub height: i64,
+    pub fees: Coin,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CallbackOutcome {
+    /// The callback height has not been reached yet
+    Pending,
+    /// The callback ran without errors and is no longer scheduled
+    Executed,
+    /// The contract returned an error, which was registered in the cwerrors module
+    Failed(ContractError),
+}
+
+/// Extension for Callback module
+/// It has ability to access to `ArchwayApp` which is more specific than `Runner`
+pub struct CallbackWithAppAccess<'a> {
+    callback: Callback<'a, ArchwayApp>,
+    app: &'a ArchwayApp,
+}
+
+impl<'a> CallbackWithAppAccess<'a> {
+    pub fn new(app: &'a ArchwayApp) -> Self {
+        Self {
+            callback: Callback::new(app),
+            app,
+        }
+    }
+
+    pub fn to_callback(&self) -> &Callback<'a, ArchwayApp> {
+        &self.callback
+    }
+
+    pub fn schedule_callback(
+        &self,
+        contract: impl Into<String>,
+        job_id: u64,
+        height: i64,
+        fees: Option<Coin>,
+        signer: &SigningAccount,
+    ) -> RunnerResult<ScheduledCallback> {
+        self.callback
+            .schedule_callback(contract, job_id, height, fees, signer)
+    }
+
+    /// Skips blocks until the callback height is executed and reports its outcome
+    pub fn run_until_callback(
+        &self,
+        callback: &ScheduledCallback,
+    ) -> RunnerResult<CallbackOutcome> {
+        while self.app.get_block_height()? < callback.height {
+            self.app.skip_block()?;
+        }
+
+        self.callback_outcome(callback)
+    }
+
+    /// Reports the outcome of the callback. It is only reported as executed once its height was
+    /// reached, the module no longer schedules it and cwerrors holds no failure for it, a
+    /// callback still scheduled past its height is an error. Cancelled callbacks are dropped as
+    /// well, so they must not be passed here
+    pub fn callback_outcome(&self, callback: &ScheduledCallback) -> RunnerResult<CallbackOutcome> {
+        if self.app.get_block_height()? < callback.height {
+            return Ok(CallbackOutcome::Pending);
+        }
+
+        // Failed callbacks store the sudo payload `{"callback":{"job_id":...}}`
+        let failure = CwErrors::new(self.app)
+            .contract_errors(callback.contract.clone())?
+            .into_iter()
+            .find(|error| {
+                error.module == ErrorModule::Callback
+                    && error
+                        .payload::<serde_json::Value>()
+                        .ok()
+                        .and_then(|payload| payload["callback"]["job_id"].as_u64())
+                        == Some(callback.job_id)
+            });
+        if let Some(error) = failure {
+            return Ok(CallbackOutcome::Failed(error));
+        }
+
+        // The module drops callbacks once it runs them, whether they succeed or not
+        let scheduled = self
+            .callback
+            .query_callbacks(&QueryCallbacksRequest {
+                block_height: callback.height,
+            })?
+            .callbacks
+            .into_iter()
+            .any(|scheduled| {
+                scheduled.contract_address == callback.contract
+                    && scheduled.job_id == callback.job_id
+            });
+        if scheduled {
+            return Err(RunnerError::QueryError {
+                msg: format!(
+                    "callback {} of {} is still scheduled after height {}",
+                    callback.job_id, callback.contract, callback.height
+                ),
+            });
+        }
+
+        Ok(CallbackOutcome::Executed)
+    }
 }
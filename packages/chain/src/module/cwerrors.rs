@@ -1,5 +1,13 @@
-use archway_proto::archway::cwerrors::v1::{QueryErrorsRequest, QueryErrorsResponse};
-use test_tube::{fn_query, Module, Runner};
+use archway_proto::archway::cwerrors::v1::{
+    MsgSubscribeToError, MsgSubscribeToErrorResponse, QueryErrorsRequest, QueryErrorsResponse,
+    QueryIsSubscribedRequest, QueryIsSubscribedResponse, QueryParamsRequest, QueryParamsResponse,
+    SudoError,
+};
+use serde::de::DeserializeOwned;
+use test_tube::{
+    fn_execute, fn_query, Account, DecodeError, Module, Runner, RunnerError, RunnerExecuteResult,
+    RunnerResult, SigningAccount,
+};
 
 pub struct CwErrors<'a, R: Runner<'a>> {
     runner: &'a R,
@@ -18,4 +26,131 @@ where
     fn_query! {
         pub errors ["/archway.cwerrors.v1.Query/Errors"]: QueryErrorsRequest => QueryErrorsResponse
     }
+
+    fn_query! {
+        pub query_is_subscribed ["/archway.cwerrors.v1.Query/IsSubscribed"]: QueryIsSubscribedRequest => QueryIsSubscribedResponse
+    }
+
+    fn_query! {
+        pub query_params ["/archway.cwerrors.v1.Query/Params"]: QueryParamsRequest => QueryParamsResponse
+    }
+
+    fn_execute! {
+        pub _subscribe_to_error: MsgSubscribeToError["/archway.cwerrors.v1.MsgSubscribeToError"] => MsgSubscribeToErrorResponse
+    }
+
+    /// Subscribes the contract to receive its errors through sudo, paying the subscription fee.
+    /// The signer must be the contract admin or its metadata owner
+    pub fn subscribe_to_error(
+        &self,
+        contract: impl Into<String>,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgSubscribeToErrorResponse> {
+        let fee = self
+            .query_params(&QueryParamsRequest {})?
+            .params
+            .and_then(|params| params.subscription_fee);
+
+        self._subscribe_to_error(
+            MsgSubscribeToError {
+                sender: signer.address(),
+                contract_address: contract.into(),
+                fee,
+            },
+            signer,
+        )
+    }
+
+    pub fn is_subscribed(&self, contract: impl Into<String>) -> RunnerResult<bool> {
+        self.query_is_subscribed(&QueryIsSubscribedRequest {
+            contract_address: contract.into(),
+        })
+        .map(|res| res.subscribed)
+    }
+
+    /// Queries the errors stored for the contract
+    pub fn contract_errors(&self, contract: impl Into<String>) -> RunnerResult<Vec<ContractError>> {
+        self.errors(&QueryErrorsRequest {
+            contract_address: contract.into(),
+        })
+        .map(|res| res.errors.into_iter().map(ContractError::from).collect())
+    }
+
+    /// Finds the latest error the module registered for the contract and sent it through sudo,
+    /// failing if there are none or if the contract is not subscribed and thus never received it
+    pub fn find_error(&self, contract: &str, module: ErrorModule) -> RunnerResult<ContractError> {
+        if !self.is_subscribed(contract)? {
+            return Err(RunnerError::QueryError {
+                msg: format!("{} is not subscribed to errors", contract),
+            });
+        }
+
+        self.contract_errors(contract)?
+            .into_iter()
+            .rev()
+            .find(|error| error.module == module)
+            .ok_or_else(|| RunnerError::QueryError {
+                msg: format!("{} has no errors from {}", contract, module.name()),
+            })
+    }
+}
+
+/// Module that registered a contract error
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorModule {
+    Callback,
+    CwIca,
+    Other(String),
+}
+
+impl ErrorModule {
+    pub fn name(&self) -> &str {
+        match self {
+            ErrorModule::Callback => "callback",
+            ErrorModule::CwIca => "cwica",
+            ErrorModule::Other(name) => name,
+        }
+    }
+}
+
+impl From<String> for ErrorModule {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "callback" => ErrorModule::Callback,
+            "cwica" => ErrorModule::CwIca,
+            _ => ErrorModule::Other(name),
+        }
+    }
+}
+
+/// Typed `SudoError` record
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractError {
+    pub module: ErrorModule,
+    pub error_code: i32,
+    pub contract: String,
+    /// JSON payload of the operation that failed
+    pub input_payload: String,
+    pub message: String,
+}
+
+impl ContractError {
+    /// Decodes the payload of the operation that failed
+    pub fn payload<T: DeserializeOwned>(&self) -> RunnerResult<T> {
+        serde_json::from_str(&self.input_payload)
+            .map_err(DecodeError::JsonDecodeError)
+            .map_err(RunnerError::DecodeError)
+    }
+}
+
+impl From<SudoError> for ContractError {
+    fn from(error: SudoError) -> Self {
+        Self {
+            module: error.module_name.into(),
+            error_code: error.error_code,
+            contract: error.contract_address,
+            input_payload: error.input_payload,
+            message: error.error_message,
+        }
+    }
 }
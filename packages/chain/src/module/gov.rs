@@ -1,6 +1,7 @@
 use test_tube::cosmrs::tx::MessageExt;
 
 use archway_proto::cosmos::base::v1beta1::Coin;
+use archway_proto::cosmos::gov::v1 as gov_v1;
 use archway_proto::cosmos::gov::v1beta1::{
     MsgSubmitProposal, MsgSubmitProposalResponse, MsgVote, MsgVoteResponse, QueryParamsRequest,
     QueryParamsResponse, QueryProposalRequest, QueryProposalResponse, VoteOption,
@@ -43,6 +44,22 @@ where
         pub query_params ["/cosmos.gov.v1beta1.Query/Params"]: QueryParamsRequest => QueryParamsResponse
     }
 
+    fn_execute! {
+        pub submit_proposal_v1: gov_v1::MsgSubmitProposal["/cosmos.gov.v1.MsgSubmitProposal"] => gov_v1::MsgSubmitProposalResponse
+    }
+
+    fn_execute! {
+        pub vote_v1: gov_v1::MsgVote["/cosmos.gov.v1.MsgVote"] => gov_v1::MsgVoteResponse
+    }
+
+    fn_query! {
+        pub query_proposal_v1 ["/cosmos.gov.v1.Query/Proposal"]: gov_v1::QueryProposalRequest => gov_v1::QueryProposalResponse
+    }
+
+    fn_query! {
+        pub query_params_v1 ["/cosmos.gov.v1.Query/Params"]: gov_v1::QueryParamsRequest => gov_v1::QueryParamsResponse
+    }
+
     pub fn submit_executable_proposal<M: prost::Message>(
         &self,
         msg_type_url: String,
@@ -153,7 +170,57 @@ impl<'a> GovWithAppAccess<'a> {
             .expect("voting period must exist");
 
         // increase time to pass voting period
-        self.app.increase_time(voting_period.seconds as u64 + 1);
+        self.app.increase_time(voting_period.seconds as u64 + 1)?;
+
+        Ok(submit_proposal_res)
+    }
+
+    /// Submits the messages as a gov v1 proposal with the minimum deposit, votes yes with the
+    /// validator and waits out the voting period, so the messages are executed by the gov module.
+    /// The messages must be signed by the gov module account
+    pub fn propose_and_execute_msgs(
+        &self,
+        msgs: Vec<Any>,
+        title: String,
+        summary: String,
+        proposer: String,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<gov_v1::MsgSubmitProposalResponse> {
+        let params = self
+            .gov
+            .query_params_v1(&gov_v1::QueryParamsRequest {
+                params_type: "deposit".to_string(),
+            })?
+            .params
+            .expect("gov params must exist");
+
+        let submit_proposal_res = self.gov.submit_proposal_v1(
+            gov_v1::MsgSubmitProposal {
+                messages: msgs,
+                initial_deposit: params.min_deposit,
+                proposer,
+                title,
+                summary,
+                ..Default::default()
+            },
+            signer,
+        )?;
+
+        let proposal_id = submit_proposal_res.data.proposal_id;
+
+        let val = self.app.get_first_validator_signing_account()?;
+        self.gov.vote_v1(
+            gov_v1::MsgVote {
+                proposal_id,
+                voter: val.address(),
+                option: gov_v1::VoteOption::Yes.into(),
+                ..Default::default()
+            },
+            &val,
+        )?;
+
+        let voting_period = params.voting_period.expect("voting period must exist");
+        self.app.increase_time(voting_period.seconds as u64 + 1)?;
 
         Ok(submit_proposal_res)
     }
@@ -8,20 +8,26 @@ mod distribution;
 mod gov;
 mod rewards;
 mod staking;
+mod tracking;
+mod transfer;
+mod upgrade;
 mod wasm;
 
-pub use authz::Authz;
+pub use authz::{contract_grant, Authz, AuthzMsg, GrantFilter, GrantLimit};
 pub use bank::Bank;
-pub use callback::Callback;
-pub use cwerrors::CwErrors;
+pub use callback::{Callback, CallbackOutcome, CallbackWithAppAccess, ScheduledCallback};
+pub use cwerrors::{ContractError, CwErrors, ErrorModule};
 pub use cwfees::CwFees;
 pub use cwica::CwIca;
 pub use distribution::Distribution;
 pub use gov::{Gov, GovWithAppAccess};
-pub use rewards::Rewards;
+pub use rewards::{ContractRewardsReport, Rewards, RewardsWithAppAccess};
 pub use staking::Staking;
 pub use test_tube::macros;
 pub use test_tube::module::Module;
+pub use tracking::Tracking;
+pub use transfer::{DenomTrace, Transfer, TRANSFER_PORT, TRANSFER_VERSION};
+pub use upgrade::Upgrade;
 pub use wasm::Wasm;
 
 pub fn type_url(url: &str) -> String {
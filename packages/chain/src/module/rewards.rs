@@ -1,13 +1,23 @@
+use crate::module::Tracking;
+use crate::{try_from_proto, ArchwayApp};
+use archway_proto::archway::rewards::v1::msg_withdraw_rewards::{Mode, RecordsLimit};
 use archway_proto::archway::rewards::v1::{
-    MsgSetContractMetadata, MsgSetContractMetadataResponse, MsgSetFlatFee, MsgSetFlatFeeResponse,
-    MsgWithdrawRewards, MsgWithdrawRewardsResponse, QueryBlockRewardsTrackingRequest,
-    QueryBlockRewardsTrackingResponse, QueryContractMetadataRequest, QueryContractMetadataResponse,
-    QueryEstimateTxFeesRequest, QueryEstimateTxFeesResponse, QueryFlatFeeRequest,
-    QueryFlatFeeResponse, QueryOutstandingRewardsRequest, QueryOutstandingRewardsResponse,
+    ContractMetadata, MsgSetContractMetadata, MsgSetContractMetadataResponse, MsgSetFlatFee,
+    MsgSetFlatFeeResponse, MsgWithdrawRewards, MsgWithdrawRewardsResponse,
+    QueryBlockRewardsTrackingRequest, QueryBlockRewardsTrackingResponse,
+    QueryContractMetadataRequest, QueryContractMetadataResponse, QueryEstimateTxFeesRequest,
+    QueryEstimateTxFeesResponse, QueryFlatFeeRequest, QueryFlatFeeResponse,
+    QueryMinConsensusFeeRequest, QueryMinConsensusFeeResponse, QueryOutstandingRewardsRequest,
+    QueryOutstandingRewardsResponse, QueryParamsRequest, QueryParamsResponse,
     QueryRewardsPoolRequest, QueryRewardsPoolResponse, QueryRewardsRecordsRequest,
     QueryRewardsRecordsResponse,
 };
-use test_tube::{fn_execute, fn_query, Module, Runner};
+use archway_proto::archway::tracking::v1::QueryBlockGasTrackingRequest;
+use cosmwasm_std::Coin;
+use test_tube::{
+    fn_execute, fn_query, Account, Module, Runner, RunnerError, RunnerExecuteResult, RunnerResult,
+    SigningAccount,
+};
 
 pub struct Rewards<'a, R: Runner<'a>> {
     runner: &'a R,
@@ -41,9 +51,15 @@ where
     fn_query! {
         pub query_outstanding_rewards ["/archway.rewards.v1.Query/OutstandingRewards"]: QueryOutstandingRewardsRequest => QueryOutstandingRewardsResponse
     }
+    fn_query! {
+        pub query_min_consensus_fee ["/archway.rewards.v1.Query/MinConsensusFee"]: QueryMinConsensusFeeRequest => QueryMinConsensusFeeResponse
+    }
     fn_query! {
         pub query_flat_fee ["/archway.rewards.v1.Query/FlatFee"]: QueryFlatFeeRequest => QueryFlatFeeResponse
     }
+    fn_query! {
+        pub query_params ["/archway.rewards.v1.Query/Params"]: QueryParamsRequest => QueryParamsResponse
+    }
     fn_execute! {
         pub set_contract_metadata: MsgSetContractMetadata["/archway.rewards.v1.MsgSetContractMetadata"] => MsgSetContractMetadataResponse
     }
@@ -53,4 +69,252 @@ where
     fn_execute! {
         pub set_flat_fee: MsgSetFlatFee["/archway.rewards.v1.MsgSetFlatFee"] => MsgSetFlatFeeResponse
     }
+
+    /// Sets the contract owner and the address that receives the contract's rewards,
+    /// the signer must be the contract admin or its current metadata owner
+    pub fn set_rewards_address(
+        &self,
+        contract: impl Into<String>,
+        owner: &SigningAccount,
+        rewards_address: impl Into<String>,
+    ) -> RunnerExecuteResult<MsgSetContractMetadataResponse> {
+        let contract_address = contract.into();
+        self.set_contract_metadata(
+            MsgSetContractMetadata {
+                sender_address: owner.address(),
+                metadata: Some(ContractMetadata {
+                    contract_address,
+                    owner_address: owner.address(),
+                    rewards_address: rewards_address.into(),
+                    ..Default::default()
+                }),
+            },
+            owner,
+        )
+    }
+
+    /// Withdraws all the rewards records available for the signer
+    pub fn withdraw_all(
+        &self,
+        rewards_owner: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgWithdrawRewardsResponse> {
+        self.withdraw_rewards(
+            MsgWithdrawRewards {
+                rewards_address: rewards_owner.address(),
+                // A limit of zero falls back to the module's max withdraw records
+                mode: Some(Mode::RecordsLimit(RecordsLimit { limit: 0 })),
+            },
+            rewards_owner,
+        )
+    }
+
+    pub fn contract_metadata(
+        &self,
+        contract: impl Into<String>,
+    ) -> RunnerResult<QueryContractMetadataResponse> {
+        self.query_contract_metadata(&QueryContractMetadataRequest {
+            contract_address: contract.into(),
+        })
+    }
+
+    pub fn outstanding_rewards(
+        &self,
+        rewards_address: impl Into<String>,
+    ) -> RunnerResult<QueryOutstandingRewardsResponse> {
+        self.query_outstanding_rewards(&QueryOutstandingRewardsRequest {
+            rewards_address: rewards_address.into(),
+        })
+    }
+
+    pub fn rewards_records(
+        &self,
+        rewards_address: impl Into<String>,
+    ) -> RunnerResult<QueryRewardsRecordsResponse> {
+        self.query_rewards_records(&QueryRewardsRecordsRequest {
+            rewards_address: rewards_address.into(),
+            pagination: None,
+        })
+    }
+}
+
+/// Rewards a contract earned from a single block
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractRewardsReport {
+    pub height: i64,
+    /// Gas consumed by the contract in the block
+    pub contract_gas: u64,
+    /// Gas consumed by the transactions that touched the contract
+    pub tx_gas: u64,
+    /// Share of the transaction fee rebates
+    pub fee_rewards: Vec<Coin>,
+    /// Share of the block inflation rewards
+    pub inflation_rewards: Vec<Coin>,
+}
+
+/// Extension for Rewards module
+/// It has ability to access to `ArchwayApp` which is more specific than `Runner`
+pub struct RewardsWithAppAccess<'a> {
+    rewards: Rewards<'a, ArchwayApp>,
+    app: &'a ArchwayApp,
+}
+
+impl<'a> RewardsWithAppAccess<'a> {
+    pub fn new(app: &'a ArchwayApp) -> Self {
+        Self {
+            rewards: Rewards::new(app),
+            app,
+        }
+    }
+
+    pub fn to_rewards(&self) -> &Rewards<'a, ArchwayApp> {
+        &self.rewards
+    }
+
+    pub fn set_rewards_address(
+        &self,
+        contract: impl Into<String>,
+        owner: &SigningAccount,
+        rewards_address: impl Into<String>,
+    ) -> RunnerExecuteResult<MsgSetContractMetadataResponse> {
+        self.rewards
+            .set_rewards_address(contract, owner, rewards_address)
+    }
+
+    /// Skips the given amount of blocks so rewards get distributed and withdraws them
+    pub fn accrue_and_withdraw(
+        &self,
+        rewards_owner: &SigningAccount,
+        blocks: u64,
+    ) -> RunnerExecuteResult<MsgWithdrawRewardsResponse> {
+        self.app.skip_blocks(blocks)?;
+        self.rewards.withdraw_all(rewards_owner)
+    }
+
+    /// Estimates the fee of a signed transaction, including the contract's flat fee if any
+    pub fn estimate_fee_for_tx(
+        &self,
+        tx_bytes: &[u8],
+        contract: Option<String>,
+    ) -> RunnerResult<QueryEstimateTxFeesResponse> {
+        let gas_info = self.app.simulate_tx_bytes(tx_bytes)?;
+        self.rewards
+            .query_estimate_tx_fees(&QueryEstimateTxFeesRequest {
+                gas_limit: gas_info.gas_used,
+                contract_address: contract.unwrap_or_default(),
+            })
+    }
+
+    /// Reports the rewards the contract earned in the latest block, since every
+    /// transaction runs in its own block this must be called right after executing it
+    pub fn last_tx_report(&self, contract: &str) -> RunnerResult<ContractRewardsReport> {
+        let tracking = Tracking::new(self.app)
+            .query_block_gas_tracking(&QueryBlockGasTrackingRequest {})?
+            .block
+            .unwrap_or_default();
+        let rewards = self
+            .rewards
+            .query_block_rewards_tracking(&QueryBlockRewardsTrackingRequest {})?
+            .block
+            .unwrap_or_default();
+
+        let mut report = ContractRewardsReport {
+            height: self.app.get_block_height()?,
+            contract_gas: 0,
+            tx_gas: 0,
+            fee_rewards: vec![],
+            inflation_rewards: vec![],
+        };
+
+        let mut block_gas = 0u64;
+        for tx in tracking.txs.iter() {
+            let Some(info) = &tx.info else {
+                continue;
+            };
+            block_gas += info.total_gas;
+
+            let contract_gas: u64 = tx
+                .contract_operations
+                .iter()
+                .filter(|op| op.contract_address == contract)
+                .map(|op| op.vm_gas + op.sdk_gas)
+                .sum();
+            if contract_gas == 0 || info.total_gas == 0 {
+                continue;
+            }
+
+            report.contract_gas += contract_gas;
+            report.tx_gas += info.total_gas;
+
+            // Fee rebates are split across the tx contracts proportionally to the gas they used
+            for tx_rewards in rewards.tx_rewards.iter().filter(|r| r.tx_id == info.id) {
+                for coin in tx_rewards.fee_rewards.iter() {
+                    let mut coin = try_from_proto(coin)?;
+                    coin.amount = coin.amount.multiply_ratio(contract_gas, info.total_gas);
+                    add_coin(&mut report.fee_rewards, coin);
+                }
+            }
+        }
+
+        // Inflation rewards are split proportionally to the block gas limit
+        if let Some(block_rewards) = rewards.inflation_rewards {
+            let max_gas = match block_rewards.max_gas {
+                0 => block_gas,
+                max_gas => max_gas,
+            };
+            if let (Some(coin), true) = (block_rewards.inflation_rewards, max_gas > 0) {
+                let mut coin = try_from_proto(&coin)?;
+                coin.amount = coin.amount.multiply_ratio(report.contract_gas, max_gas);
+                add_coin(&mut report.inflation_rewards, coin);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Rewards paid to the rewards address of the contract for the block at the given height.
+    /// They are recorded in the following block, which must be committed first.
+    /// Empty when the contract has no rewards address
+    pub fn paid_rewards(&self, contract: &str, height: i64) -> RunnerResult<Vec<Coin>> {
+        let Some(rewards_address) = self.rewards_address(contract)? else {
+            return Ok(vec![]);
+        };
+
+        let mut paid = vec![];
+        for record in self
+            .rewards
+            .rewards_records(rewards_address)?
+            .records
+            .iter()
+            .filter(|record| record.calculated_height == height + 1)
+        {
+            for coin in record.rewards.iter() {
+                add_coin(&mut paid, try_from_proto(coin)?);
+            }
+        }
+
+        Ok(paid)
+    }
+
+    fn rewards_address(&self, contract: &str) -> RunnerResult<Option<String>> {
+        match self.rewards.contract_metadata(contract) {
+            Ok(res) => Ok(res
+                .metadata
+                .map(|metadata| metadata.rewards_address)
+                .filter(|address| !address.is_empty())),
+            // The query fails for contracts that never had metadata set
+            Err(RunnerError::QueryError { msg }) if msg.contains("not found") => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+fn add_coin(coins: &mut Vec<Coin>, coin: Coin) {
+    if coin.amount.is_zero() {
+        return;
+    }
+
+    match coins.iter_mut().find(|c| c.denom == coin.denom) {
+        Some(existing) => existing.amount += coin.amount,
+        None => coins.push(coin),
+    }
 }
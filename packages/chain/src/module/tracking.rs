@@ -0,0 +1,28 @@
+use archway_proto::archway::tracking::v1::{
+    QueryBlockGasTrackingRequest, QueryBlockGasTrackingResponse, QueryParamsRequest,
+    QueryParamsResponse,
+};
+use test_tube::{fn_query, Module, Runner};
+
+pub struct Tracking<'a, R: Runner<'a>> {
+    runner: &'a R,
+}
+
+impl<'a, R: Runner<'a>> Module<'a, R> for Tracking<'a, R> {
+    fn new(runner: &'a R) -> Self {
+        Self { runner }
+    }
+}
+
+impl<'a, R> Tracking<'a, R>
+where
+    R: Runner<'a>,
+{
+    fn_query! {
+        pub query_block_gas_tracking ["/archway.tracking.v1.Query/BlockGasTracking"]: QueryBlockGasTrackingRequest => QueryBlockGasTrackingResponse
+    }
+
+    fn_query! {
+        pub query_params ["/archway.tracking.v1.Query/Params"]: QueryParamsRequest => QueryParamsResponse
+    }
+}
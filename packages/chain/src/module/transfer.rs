@@ -0,0 +1,149 @@
+use crate::to_proto;
+use cosmwasm_std::Coin;
+use ibc_proto::ibc::apps::transfer::v1::{
+    MsgTransfer, MsgTransferResponse, QueryDenomTraceRequest, QueryDenomTraceResponse,
+};
+use sha2::{Digest, Sha256};
+use test_tube::{
+    fn_execute, fn_query, Account, Module, Runner, RunnerError, RunnerExecuteResult, RunnerResult,
+    SigningAccount,
+};
+
+pub const TRANSFER_PORT: &str = "transfer";
+pub const TRANSFER_VERSION: &str = "ics20-1";
+
+pub struct Transfer<'a, R: Runner<'a>> {
+    runner: &'a R,
+}
+
+impl<'a, R: Runner<'a>> Module<'a, R> for Transfer<'a, R> {
+    fn new(runner: &'a R) -> Self {
+        Self { runner }
+    }
+}
+
+impl<'a, R> Transfer<'a, R>
+where
+    R: Runner<'a>,
+{
+    fn_execute! {
+        pub _transfer: MsgTransfer["/ibc.applications.transfer.v1.MsgTransfer"] => MsgTransferResponse
+    }
+
+    fn_query! {
+        pub query_denom_trace ["/ibc.applications.transfer.v1.Query/DenomTrace"]: QueryDenomTraceRequest => QueryDenomTraceResponse
+    }
+
+    /// Sends the token through the transfer channel, the packet times out once the
+    /// receiving chain reaches the timestamp in nanoseconds
+    pub fn transfer(
+        &self,
+        channel: impl Into<String>,
+        token: &Coin,
+        receiver: impl Into<String>,
+        timeout_timestamp: u64,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgTransferResponse> {
+        self._transfer(
+            MsgTransfer {
+                source_port: TRANSFER_PORT.to_string(),
+                source_channel: channel.into(),
+                token: Some(to_proto(token)),
+                sender: signer.address(),
+                receiver: receiver.into(),
+                timeout_height: None,
+                timeout_timestamp,
+                ..Default::default()
+            },
+            signer,
+        )
+    }
+
+    /// Resolves the trace of a denom, the traces of the `ibc/` vouchers are queried from the
+    /// transfer module
+    pub fn denom_trace(&self, denom: &str) -> RunnerResult<DenomTrace> {
+        let Some(hash) = denom.strip_prefix("ibc/") else {
+            return Ok(DenomTrace {
+                path: String::new(),
+                base_denom: denom.to_string(),
+            });
+        };
+
+        let trace = self
+            .query_denom_trace(&QueryDenomTraceRequest {
+                hash: hash.to_string(),
+            })?
+            .denom_trace
+            .ok_or_else(|| RunnerError::QueryError {
+                msg: format!("no denom trace found for {}", denom),
+            })?;
+
+        Ok(DenomTrace {
+            path: trace.path,
+            base_denom: trace.base_denom,
+        })
+    }
+}
+
+/// ICS-20 trace of the channels a token went through
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DenomTrace {
+    /// Port and channel pairs, e.g. `transfer/channel-0`
+    pub path: String,
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    pub fn full_path(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", self.path, self.base_denom)
+        }
+    }
+
+    /// Denom of the token on the chain holding it
+    pub fn ibc_denom(&self) -> String {
+        if self.path.is_empty() {
+            return self.base_denom.clone();
+        }
+
+        let hash = Sha256::digest(self.full_path().as_bytes());
+        let hex: String = hash.iter().map(|byte| format!("{:02X}", byte)).collect();
+        format!("ibc/{}", hex)
+    }
+
+    /// Trace of the token once received through the channel, tokens returning
+    /// through the channel they came from are unwound
+    pub fn received(
+        &self,
+        source_port: &str,
+        source_channel: &str,
+        destination_port: &str,
+        destination_channel: &str,
+    ) -> Self {
+        let source_prefix = format!("{}/{}", source_port, source_channel);
+        if self.path == source_prefix {
+            return Self {
+                path: String::new(),
+                base_denom: self.base_denom.clone(),
+            };
+        }
+        if let Some(path) = self.path.strip_prefix(&format!("{}/", source_prefix)) {
+            return Self {
+                path: path.to_string(),
+                base_denom: self.base_denom.clone(),
+            };
+        }
+
+        let destination_prefix = format!("{}/{}", destination_port, destination_channel);
+        Self {
+            path: if self.path.is_empty() {
+                destination_prefix
+            } else {
+                format!("{}/{}", destination_prefix, self.path)
+            },
+            base_denom: self.base_denom.clone(),
+        }
+    }
+}
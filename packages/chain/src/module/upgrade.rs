@@ -0,0 +1,32 @@
+use archway_proto::cosmos::upgrade::v1beta1::{
+    QueryAppliedPlanRequest, QueryAppliedPlanResponse, QueryCurrentPlanRequest,
+    QueryCurrentPlanResponse, QueryModuleVersionsRequest, QueryModuleVersionsResponse,
+};
+use test_tube::{fn_query, Module, Runner};
+
+pub struct Upgrade<'a, R: Runner<'a>> {
+    runner: &'a R,
+}
+
+impl<'a, R: Runner<'a>> Module<'a, R> for Upgrade<'a, R> {
+    fn new(runner: &'a R) -> Self {
+        Self { runner }
+    }
+}
+
+impl<'a, R> Upgrade<'a, R>
+where
+    R: Runner<'a>,
+{
+    fn_query! {
+        pub query_current_plan ["/cosmos.upgrade.v1beta1.Query/CurrentPlan"]: QueryCurrentPlanRequest => QueryCurrentPlanResponse
+    }
+
+    fn_query! {
+        pub query_applied_plan ["/cosmos.upgrade.v1beta1.Query/AppliedPlan"]: QueryAppliedPlanRequest => QueryAppliedPlanResponse
+    }
+
+    fn_query! {
+        pub query_module_versions ["/cosmos.upgrade.v1beta1.Query/ModuleVersions"]: QueryModuleVersionsRequest => QueryModuleVersionsResponse
+    }
+}
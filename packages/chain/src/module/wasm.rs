@@ -1,8 +1,10 @@
 use crate::module::type_url;
+use archway_proto::cosmos::base::query::v1beta1::PageRequest;
 use archway_proto::cosmwasm::wasm::v1::{
     AccessConfig, MsgExecuteContract, MsgExecuteContractResponse, MsgInstantiateContract,
     MsgInstantiateContractResponse, MsgMigrateContract, MsgMigrateContractResponse, MsgStoreCode,
-    MsgStoreCodeResponse, QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+    MsgStoreCodeResponse, QueryAllContractStateRequest, QueryAllContractStateResponse,
+    QuerySmartContractStateRequest, QuerySmartContractStateResponse,
 };
 use cosmwasm_std::Coin;
 use prost::Name;
@@ -144,4 +146,20 @@ where
             .map_err(DecodeError::JsonDecodeError)
             .map_err(RunnerError::DecodeError)
     }
+
+    /// Queries the raw key value pairs stored by the contract
+    pub fn all_contract_state(
+        &self,
+        contract: &str,
+        pagination: Option<PageRequest>,
+    ) -> RunnerResult<QueryAllContractStateResponse> {
+        self.runner
+            .query::<QueryAllContractStateRequest, QueryAllContractStateResponse>(
+                "/cosmwasm.wasm.v1.Query/AllContractState",
+                &QueryAllContractStateRequest {
+                    address: contract.to_owned(),
+                    pagination,
+                },
+            )
+    }
 }
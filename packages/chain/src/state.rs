@@ -0,0 +1,154 @@
+use cosmwasm_std::Binary;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use test_tube::runner::error::DecodeError;
+use test_tube::RunnerResult;
+
+/// Raw storage of a contract at a given block height, taken with
+/// [`crate::ArchwayApp::contract_state_snapshot`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContractState {
+    pub contract: String,
+    pub height: i64,
+    pub entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl ContractState {
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&[u8]> {
+        self.entries.get(key.as_ref()).map(Vec::as_slice)
+    }
+
+    /// Decodes the JSON value stored under the key
+    pub fn get_json<T: DeserializeOwned>(&self, key: impl AsRef<[u8]>) -> RunnerResult<Option<T>> {
+        self.get(key).map(decode_json).transpose()
+    }
+
+    /// Size of the stored keys and values
+    pub fn bytes(&self) -> u64 {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.len() + value.len()) as u64)
+            .sum()
+    }
+
+    /// Changes needed to go from this snapshot to a later one
+    pub fn diff(&self, after: &ContractState) -> StateDiff {
+        let mut diff = StateDiff::default();
+
+        for (key, value) in after.entries.iter() {
+            match self.entries.get(key) {
+                None => diff.added.push(StateEntry::new(key, value)),
+                Some(before) if before != value => diff.changed.push(StateChange {
+                    key: Binary::from(key.as_slice()),
+                    before: Binary::from(before.as_slice()),
+                    after: Binary::from(value.as_slice()),
+                }),
+                Some(_) => {}
+            }
+        }
+
+        for (key, value) in self.entries.iter() {
+            if !after.entries.contains_key(key) {
+                diff.removed.push(StateEntry::new(key, value));
+            }
+        }
+
+        diff
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateEntry {
+    pub key: Binary,
+    pub value: Binary,
+}
+
+impl StateEntry {
+    fn new(key: &[u8], value: &[u8]) -> Self {
+        Self {
+            key: Binary::from(key),
+            value: Binary::from(value),
+        }
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> RunnerResult<T> {
+        decode_json(&self.value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateChange {
+    pub key: Binary,
+    pub before: Binary,
+    pub after: Binary,
+}
+
+impl StateChange {
+    pub fn before_json<T: DeserializeOwned>(&self) -> RunnerResult<T> {
+        decode_json(&self.before)
+    }
+
+    pub fn after_json<T: DeserializeOwned>(&self) -> RunnerResult<T> {
+        decode_json(&self.after)
+    }
+}
+
+/// Keys added, changed and removed between two [`ContractState`] snapshots, sorted by key
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    pub added: Vec<StateEntry>,
+    pub changed: Vec<StateChange>,
+    pub removed: Vec<StateEntry>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn get_added(&self, key: impl AsRef<[u8]>) -> Option<&StateEntry> {
+        self.added
+            .iter()
+            .find(|entry| entry.key.as_slice() == key.as_ref())
+    }
+
+    pub fn get_changed(&self, key: impl AsRef<[u8]>) -> Option<&StateChange> {
+        self.changed
+            .iter()
+            .find(|change| change.key.as_slice() == key.as_ref())
+    }
+
+    pub fn get_removed(&self, key: impl AsRef<[u8]>) -> Option<&StateEntry> {
+        self.removed
+            .iter()
+            .find(|entry| entry.key.as_slice() == key.as_ref())
+    }
+
+    /// Size of the keys and values written by the added and changed entries
+    pub fn bytes_written(&self) -> u64 {
+        let added: usize = self
+            .added
+            .iter()
+            .map(|entry| entry.key.len() + entry.value.len())
+            .sum();
+        let changed: usize = self
+            .changed
+            .iter()
+            .map(|change| change.key.len() + change.after.len())
+            .sum();
+
+        (added + changed) as u64
+    }
+
+    /// Size of the keys and values dropped by the removed entries
+    pub fn bytes_removed(&self) -> u64 {
+        self.removed
+            .iter()
+            .map(|entry| (entry.key.len() + entry.value.len()) as u64)
+            .sum()
+    }
+}
+
+fn decode_json<T: DeserializeOwned>(value: &[u8]) -> RunnerResult<T> {
+    Ok(serde_json::from_slice(value).map_err(DecodeError::JsonDecodeError)?)
+}
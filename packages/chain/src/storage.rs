@@ -0,0 +1,55 @@
+use crate::json_event_attribute;
+use cosmwasm_std::{Binary, Event};
+use serde::{Deserialize, Serialize};
+use test_tube::RunnerResult;
+
+/// Event attached to tx results once storage tracing is enabled with
+/// [`crate::ArchwayApp::set_storage_tracing`]
+pub const STORAGE_TRACE_EVENT: &str = "litmus_storage_trace";
+const ACCESSES_ATTRIBUTE: &str = "accesses";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageOperation {
+    Read,
+    Write,
+    Delete,
+    Has,
+    /// Entry reached by an iterator
+    Iterate,
+}
+
+/// KV store operation performed by a tx
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageAccess {
+    pub operation: StorageOperation,
+    /// Name of the module store, e.g. `wasm`
+    pub store: String,
+    pub key: Binary,
+    /// Contract owning the key, when it belongs to a contract's storage
+    #[serde(default)]
+    pub contract: Option<String>,
+    /// Key within the contract's storage
+    #[serde(default)]
+    pub contract_key: Option<Binary>,
+    pub value_size: u64,
+    /// Gas charged by the store for the operation
+    pub gas: u64,
+    /// Whether the operation was done by the ante handler
+    pub ante: bool,
+}
+
+impl StorageAccess {
+    /// Reads the trace from the events of an execution, it is only there when storage tracing is enabled
+    pub fn from_events(events: &[Event]) -> RunnerResult<Option<Vec<Self>>> {
+        json_event_attribute(events, STORAGE_TRACE_EVENT, ACCESSES_ATTRIBUTE)
+    }
+
+    /// Namespace of the contract key when it was written by a `cw-storage-plus` map,
+    /// which prefixes the namespace with its length
+    pub fn contract_namespace(&self) -> Option<&[u8]> {
+        let key = self.contract_key.as_ref()?.as_slice();
+        let len = u16::from_be_bytes(key.get(..2)?.try_into().ok()?) as usize;
+        key.get(2..2 + len)
+    }
+}
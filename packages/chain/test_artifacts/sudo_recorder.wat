@@ -0,0 +1,366 @@
+;; Minimal CosmWasm contract for the tests of the sudo driven modules (callback, cwerrors,
+;; cwfees and cwica), written by hand since their flows only need a handful of behaviours:
+;;
+;; - instantiate takes any message, prints "sudo recorder instantiated" with deps.api.debug
+;;   and returns an empty response
+;; - execute takes {"messages":[<SubMsg>...]} and returns a response with these submessages,
+;;   so the contract sends whatever the test builds, as the contract
+;; - sudo stores every message it receives under "sudo" + its big endian index, the number of
+;;   messages being stored under "count", and accepts them all but the callbacks with an odd
+;;   job id, which fail
+;; - query returns null
+;;
+;; Build with `wat2wasm sudo_recorder.wat -o sudo_recorder.wasm`
+(module
+  (import "env" "db_read" (func $db_read (param i32) (result i32)))
+  (import "env" "db_write" (func $db_write (param i32 i32)))
+  (import "env" "debug" (func $debug (param i32)))
+
+  (memory (export "memory") 1)
+
+  ;; Next free byte, nothing is ever freed
+  (global $heap (mut i32) (i32.const 4096))
+
+  (func $interface_version_8 (export "interface_version_8"))
+
+  ;; Returns a region of the given capacity, growing the memory if needed
+  (func $allocate (export "allocate") (param $size i32) (result i32)
+    (local $region i32)
+    (local $end i32)
+    global.get $heap
+    local.set $region
+    local.get $region
+    i32.const 12
+    i32.add
+    local.get $size
+    i32.add
+    local.set $end
+    block $fits
+      loop $grow
+        local.get $end
+        memory.size
+        i32.const 16
+        i32.shl
+        i32.le_u
+        br_if $fits
+        i32.const 1
+        memory.grow
+        i32.const -1
+        i32.eq
+        if
+          unreachable
+        end
+        br $grow
+      end
+    end
+    ;; offset, capacity and length
+    local.get $region
+    local.get $region
+    i32.const 12
+    i32.add
+    i32.store
+    local.get $region
+    local.get $size
+    i32.store offset=4
+    local.get $region
+    i32.const 0
+    i32.store offset=8
+    local.get $end
+    i32.const 3
+    i32.add
+    i32.const -4
+    i32.and
+    global.set $heap
+    local.get $region)
+
+  (func $deallocate (export "deallocate") (param $region i32))
+
+  (func $copy (param $dst i32) (param $src i32) (param $len i32)
+    block $done
+      loop $next
+        local.get $len
+        i32.eqz
+        br_if $done
+        local.get $dst
+        local.get $src
+        i32.load8_u
+        i32.store8
+        local.get $dst
+        i32.const 1
+        i32.add
+        local.set $dst
+        local.get $src
+        i32.const 1
+        i32.add
+        local.set $src
+        local.get $len
+        i32.const 1
+        i32.sub
+        local.set $len
+        br $next
+      end
+    end)
+
+  ;; Returns 1 if the len bytes at a and b are equal
+  (func $equal (param $a i32) (param $b i32) (param $len i32) (result i32)
+    block $done
+      loop $next
+        local.get $len
+        i32.eqz
+        br_if $done
+        local.get $a
+        i32.load8_u
+        local.get $b
+        i32.load8_u
+        i32.ne
+        if
+          i32.const 0
+          return
+        end
+        local.get $a
+        i32.const 1
+        i32.add
+        local.set $a
+        local.get $b
+        i32.const 1
+        i32.add
+        local.set $b
+        local.get $len
+        i32.const 1
+        i32.sub
+        local.set $len
+        br $next
+      end
+    end
+    i32.const 1)
+
+  ;; Returns a region holding the three byte ranges one after the other
+  (func $concat (param $a i32) (param $a_len i32) (param $b i32) (param $b_len i32) (param $c i32) (param $c_len i32) (result i32)
+    (local $region i32)
+    (local $dst i32)
+    local.get $a_len
+    local.get $b_len
+    i32.add
+    local.get $c_len
+    i32.add
+    call $allocate
+    local.set $region
+    local.get $region
+    i32.load
+    local.set $dst
+    local.get $dst
+    local.get $a
+    local.get $a_len
+    call $copy
+    local.get $dst
+    local.get $a_len
+    i32.add
+    local.set $dst
+    local.get $dst
+    local.get $b
+    local.get $b_len
+    call $copy
+    local.get $dst
+    local.get $b_len
+    i32.add
+    local.get $c
+    local.get $c_len
+    call $copy
+    local.get $region
+    local.get $region
+    i32.load offset=4
+    i32.store offset=8
+    local.get $region)
+
+  (func $bytes (param $ptr i32) (param $len i32) (result i32)
+    local.get $ptr
+    local.get $len
+    i32.const 0
+    i32.const 0
+    i32.const 0
+    i32.const 0
+    call $concat)
+
+  (func $store_be (param $ptr i32) (param $value i32)
+    local.get $ptr
+    local.get $value
+    i32.const 24
+    i32.shr_u
+    i32.store8
+    local.get $ptr
+    local.get $value
+    i32.const 16
+    i32.shr_u
+    i32.store8 offset=1
+    local.get $ptr
+    local.get $value
+    i32.const 8
+    i32.shr_u
+    i32.store8 offset=2
+    local.get $ptr
+    local.get $value
+    i32.store8 offset=3)
+
+  (func $load_be (param $ptr i32) (result i32)
+    local.get $ptr
+    i32.load8_u
+    i32.const 24
+    i32.shl
+    local.get $ptr
+    i32.load8_u offset=1
+    i32.const 16
+    i32.shl
+    i32.or
+    local.get $ptr
+    i32.load8_u offset=2
+    i32.const 8
+    i32.shl
+    i32.or
+    local.get $ptr
+    i32.load8_u offset=3
+    i32.or)
+
+  ;; Returns a region holding the prefix followed by the big endian value
+  (func $be_bytes (param $prefix i32) (param $prefix_len i32) (param $value i32) (result i32)
+    (local $region i32)
+    local.get $prefix_len
+    i32.const 4
+    i32.add
+    call $allocate
+    local.set $region
+    local.get $region
+    i32.load
+    local.get $prefix
+    local.get $prefix_len
+    call $copy
+    local.get $region
+    i32.load
+    local.get $prefix_len
+    i32.add
+    local.get $value
+    call $store_be
+    local.get $region
+    local.get $prefix_len
+    i32.const 4
+    i32.add
+    i32.store offset=8
+    local.get $region)
+
+  (func $instantiate (export "instantiate") (param $env i32) (param $info i32) (param $msg i32) (result i32)
+    i32.const 1244
+    i32.const 26
+    call $bytes
+    call $debug
+    i32.const 1024
+    i32.const 62
+    call $bytes)
+
+  ;; Splices the array of {"messages":[...]} into the response
+  (func $execute (export "execute") (param $env i32) (param $info i32) (param $msg i32) (result i32)
+    i32.const 1088
+    i32.const 18
+    local.get $msg
+    i32.load
+    i32.const 12
+    i32.add
+    local.get $msg
+    i32.load offset=8
+    i32.const 13
+    i32.sub
+    i32.const 1108
+    i32.const 42
+    call $concat)
+
+  (func $sudo (export "sudo") (param $env i32) (param $msg i32) (result i32)
+    (local $ptr i32)
+    (local $len i32)
+    (local $count i32)
+    (local $stored i32)
+    local.get $msg
+    i32.load
+    local.set $ptr
+    local.get $msg
+    i32.load offset=8
+    local.set $len
+    ;; {"callback":{"job_id":<id>}}, the last digit of the id being 3 bytes before the end
+    local.get $len
+    i32.const 12
+    i32.ge_u
+    if
+      local.get $ptr
+      i32.const 1220
+      i32.const 12
+      call $equal
+      if
+        local.get $ptr
+        local.get $len
+        i32.add
+        i32.const 3
+        i32.sub
+        i32.load8_u
+        i32.const 1
+        i32.and
+        if
+          i32.const 1172
+          i32.const 45
+          call $bytes
+          return
+        end
+      end
+    end
+    i32.const 1232
+    i32.const 5
+    call $bytes
+    call $db_read
+    local.set $stored
+    local.get $stored
+    if
+      local.get $stored
+      i32.load
+      call $load_be
+      local.set $count
+    end
+    i32.const 1240
+    i32.const 4
+    local.get $count
+    call $be_bytes
+    local.get $msg
+    call $db_write
+    i32.const 1232
+    i32.const 5
+    call $bytes
+    i32.const 0
+    i32.const 0
+    local.get $count
+    i32.const 1
+    i32.add
+    call $be_bytes
+    call $db_write
+    i32.const 1024
+    i32.const 62
+    call $bytes)
+
+  (func $query (export "query") (param $env i32) (param $msg i32) (result i32)
+    i32.const 1152
+    i32.const 17
+    call $bytes)
+
+  ;; OK_EMPTY
+  (data (i32.const 1024) "{\"ok\":{\"messages\":[],\"attributes\":[],\"events\":[],\"data\":null}}")
+  ;; OK_PREFIX
+  (data (i32.const 1088) "{\"ok\":{\"messages\":")
+  ;; OK_SUFFIX
+  (data (i32.const 1108) ",\"attributes\":[],\"events\":[],\"data\":null}}")
+  ;; QUERY_OK
+  (data (i32.const 1152) "{\"ok\":\"bnVsbA==\"}")
+  ;; CALLBACK_ERR
+  (data (i32.const 1172) "{\"error\":\"callbacks with an odd job id fail\"}")
+  ;; CALLBACK
+  (data (i32.const 1220) "{\"callback\":")
+  ;; COUNT
+  (data (i32.const 1232) "count")
+  ;; SUDO
+  (data (i32.const 1240) "sudo")
+  ;; INSTANTIATED
+  (data (i32.const 1244) "sudo recorder instantiated")
+)
@@ -1,8 +1,9 @@
 use crate::console::{Console, ConsoleSettings};
 use crate::harness::graph_builder::{GenericStorage, Graph, LinearGraph, Storage};
 use crate::harness::HarnessRuntime;
-use crate::job::{Continuous, Independent, Job, Setup};
+use crate::job::{Continuous, Independent, Job, Queries, QuerySetup, Setup};
 use crate::naming::{NameType, Naming};
+use litmus_chain::log::{set_log_sink, LogLevel, LogSink};
 use litmus_chain::ArchwayApp;
 use serde::Serialize;
 use tokio::runtime::Builder;
@@ -13,6 +14,8 @@ pub struct HarnessBuilder {
     pub(crate) console: Option<Box<dyn Console>>,
     pub(crate) storage: Option<Box<dyn Storage>>,
     pub(crate) graphs: Vec<Box<dyn Graph>>,
+    pub(crate) account_seed: Option<String>,
+    pub(crate) log_sink: Option<(LogSink, LogLevel)>,
 }
 
 impl Default for HarnessBuilder {
@@ -29,6 +32,8 @@ impl HarnessBuilder {
             console: None,
             storage: None,
             graphs: vec![],
+            account_seed: None,
+            log_sink: None,
         }
     }
 
@@ -92,6 +97,30 @@ impl HarnessBuilder {
         }
     }
 
+    /// Benches a smart query for every parameter, against the state returned by the setup
+    pub fn query_group<STATE, PARAM, MSG, SETUP, QUERY>(
+        &mut self,
+        name: impl ToString,
+        setup: SETUP,
+        query: QUERY,
+        parameters: Vec<PARAM>,
+    ) where
+        PARAM: Naming + Send + Sync + 'static,
+        MSG: Sized + Serialize + Send + Sync + 'static,
+        STATE: 'static,
+        SETUP: Fn(&ArchwayApp) -> STATE + 'static + Send + Sync,
+        QUERY: Fn(&ArchwayApp, &STATE, &PARAM) -> QuerySetup<MSG> + 'static + Send + Sync,
+    {
+        let group = self.add_group(name.to_string(), PARAM::name_type());
+
+        self.jobs.push(Box::new(Queries {
+            id: group,
+            parameters,
+            setup: Box::new(setup),
+            query: Box::new(query),
+        }));
+    }
+
     pub fn set_console<T: Console + 'static>(&mut self, console: T) {
         self.console = Some(Box::new(console))
     }
@@ -104,6 +133,17 @@ impl HarnessBuilder {
         self.graphs.push(Box::new(graph))
     }
 
+    /// Derives the accounts of every job from the seed, so gas figures that depend on
+    /// addresses are reproducible between runs
+    pub fn set_account_seed(&mut self, seed: impl ToString) {
+        self.account_seed = Some(seed.to_string())
+    }
+
+    /// Writes the chain logs of every job to the sink, they are discarded by default
+    pub fn set_log_sink(&mut self, sink: LogSink, level: LogLevel) {
+        self.log_sink = Some((sink, level))
+    }
+
     pub fn build_console(&mut self, settings: ConsoleSettings) {
         self.console = Some(settings.build())
     }
@@ -113,6 +153,11 @@ impl HarnessBuilder {
             self.graphs.push(Box::<LinearGraph>::default());
         }
 
+        if let Some((sink, level)) = &self.log_sink {
+            // TODO: unsafe unwrap
+            set_log_sink(sink, *level).unwrap();
+        }
+
         HarnessRuntime::new(
             self.groups,
             self.jobs,
@@ -120,6 +165,7 @@ impl HarnessBuilder {
             self.storage
                 .unwrap_or(Box::new(GenericStorage::save_last("./litmus"))),
             self.graphs,
+            self.account_seed,
             tokio_builder,
         )
     }
@@ -1,5 +1,5 @@
 use crate::naming::NameType;
-use crate::results::{BenchResults, FinalizedGroup};
+use crate::results::{BenchResult, BenchResults, FinalizedGroup};
 use plotters::chart::LabelAreaPosition;
 use plotters::element::Rectangle;
 use plotters::prelude::{
@@ -151,6 +151,13 @@ pub enum GraphTarget {
     GasWanted,
     GasUsed,
     ArchSpent,
+    FlatFee,
+    /// Gas spent on KV store accesses by the executed messages
+    StorageGas,
+    /// Gas spent running the contract in the VM
+    WasmGas,
+    /// Bytes written to the storage of the benched contract
+    BytesWritten,
 }
 
 pub struct LinearGraph {
@@ -217,6 +224,9 @@ impl Graph for LinearGraph {
             GraphTarget::GasWanted => "Gas_Wanted",
             GraphTarget::GasUsed => "Gas_Used",
             GraphTarget::ArchSpent => "aarch",
+            GraphTarget::FlatFee => "Flat_Fee_aarch",
+            GraphTarget::StorageGas => "Storage_Gas",
+            GraphTarget::WasmGas => "Wasm_Gas",
         });
 
         if name_type == NameType::Named {
@@ -249,6 +259,10 @@ impl Graph for LinearGraph {
                     GraphTarget::GasWanted => data.gas.wanted,
                     GraphTarget::GasUsed => data.gas.used,
                     GraphTarget::ArchSpent => data.arch,
+                    GraphTarget::FlatFee => data.flat_fee,
+                    GraphTarget::StorageGas => storage_gas(data),
+                    GraphTarget::WasmGas => wasm_gas(data),
+                    GraphTarget::BytesWritten => data.bytes_written as u128,
                 };
 
                 let x = match name_type {
@@ -296,6 +310,10 @@ impl LinearGraph {
                     GraphTarget::GasWanted => result.gas.wanted,
                     GraphTarget::GasUsed => result.gas.used,
                     GraphTarget::ArchSpent => result.arch,
+                    GraphTarget::FlatFee => result.flat_fee,
+                    GraphTarget::StorageGas => storage_gas(result),
+                    GraphTarget::WasmGas => wasm_gas(result),
+                    GraphTarget::BytesWritten => result.bytes_written as u128,
                 });
             }
         }
@@ -315,3 +333,19 @@ impl LinearGraph {
             ..max_gas.checked_add(size_difference).unwrap_or(u128::MAX)
     }
 }
+
+fn storage_gas(result: &BenchResult) -> u128 {
+    result
+        .gas
+        .breakdown
+        .map(|breakdown| breakdown.storage.total() as u128)
+        .unwrap_or_default()
+}
+
+fn wasm_gas(result: &BenchResult) -> u128 {
+    result
+        .gas
+        .breakdown
+        .map(|breakdown| breakdown.wasm.compute as u128)
+        .unwrap_or_default()
+}
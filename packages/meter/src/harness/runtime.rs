@@ -19,6 +19,7 @@ impl HarnessRuntime {
         mut console: Box<dyn Console>,
         storage: Box<dyn Storage>,
         graphs: Vec<Box<dyn Graph>>,
+        account_seed: Option<String>,
         mut tokio_builder: Builder,
     ) -> Self {
         // TODO: unsafe unwrap
@@ -35,8 +36,11 @@ impl HarnessRuntime {
                 let g = console_setup.get_mut(job.get_group_id()).unwrap();
                 g.1 += 1;
 
+                let account_seed = account_seed.clone();
                 running_jobs.push(tokio::spawn(async move {
-                    let app = ArchwayApp::new();
+                    let mut app = ArchwayApp::new();
+                    app.set_account_seed(account_seed);
+                    app.set_gas_tracing(true).unwrap();
                     job.run(app)
                 }));
             }
@@ -2,18 +2,72 @@ use crate::naming::Naming;
 use crate::results::{BenchResult, BenchResults, Gas};
 use cosmwasm_std::Coin;
 use litmus_chain::archway_proto::cosmos::bank::v1beta1::QueryBalanceRequest;
-use litmus_chain::module::{Bank, Module, Wasm};
-use litmus_chain::test_tube::{Account, SigningAccount};
-use litmus_chain::{ArchwayApp, FEE_DENOM};
+use litmus_chain::archway_proto::cosmwasm::wasm::v1::{
+    MsgExecuteContract, MsgExecuteContractResponse,
+};
+use litmus_chain::gas::GasBreakdown;
+use litmus_chain::module::{Bank, Module};
+use litmus_chain::test_tube::{Account, FeeSetting, SigningAccount};
+use litmus_chain::{to_proto, ArchwayApp, DEFAULT_SMART_QUERY_GAS_LIMIT, FEE_DENOM};
 use serde::Serialize;
 use std::sync::Arc;
 
-/// Output msg for the job benching
+/// Output msg for the job benching, built with [`Setup::new`] so the options can grow
+#[non_exhaustive]
 pub struct Setup<MSG> {
     pub contract: String,
     pub signer: Arc<SigningAccount>,
     pub funds: Vec<Coin>,
     pub msg: MSG,
+    /// Granting contract that pays the tx fees, used to bench contract-sponsored txs
+    pub granter: Option<String>,
+    /// Overrides the signer's fee setting, used to bench fee sensitivity
+    pub fee_setting: Option<FeeSetting>,
+    /// Only simulates the tx, so the app state is not changed between parameters.
+    /// The simulated fee is reported as sponsored when a granter is set
+    pub simulate: bool,
+}
+
+impl<MSG> Setup<MSG> {
+    pub fn new(
+        contract: impl Into<String>,
+        signer: Arc<SigningAccount>,
+        funds: Vec<Coin>,
+        msg: MSG,
+    ) -> Self {
+        Self {
+            contract: contract.into(),
+            signer,
+            funds,
+            msg,
+            granter: None,
+            fee_setting: None,
+            simulate: false,
+        }
+    }
+
+    pub fn with_granter(mut self, granter: impl Into<String>) -> Self {
+        self.granter = Some(granter.into());
+        self
+    }
+
+    pub fn with_fee_setting(mut self, fee_setting: FeeSetting) -> Self {
+        self.fee_setting = Some(fee_setting);
+        self
+    }
+
+    pub fn simulated(mut self) -> Self {
+        self.simulate = true;
+        self
+    }
+}
+
+/// Output msg for the query benching
+pub struct QuerySetup<MSG> {
+    pub contract: String,
+    pub msg: MSG,
+    /// Defaults to wasmd's smart query gas limit, which bounds the queries between contracts
+    pub gas_limit: Option<u64>,
 }
 
 pub struct JobResult {
@@ -108,6 +162,52 @@ where
     }
 }
 
+// A single threaded group benching smart queries, queries don't change the app state
+// so every parameter runs against the state built by the setup
+pub struct Queries<SETUP, QUERY, STATE, PARAM, MSG>
+where
+    SETUP: Fn(&ArchwayApp) -> STATE + Send + Sync,
+    QUERY: Fn(&ArchwayApp, &STATE, &PARAM) -> QuerySetup<MSG> + 'static + Send + Sync,
+    PARAM: Send + Sync,
+{
+    pub id: usize,
+    pub parameters: Vec<PARAM>,
+    pub setup: Box<SETUP>,
+    pub query: Box<QUERY>,
+}
+
+impl<SETUP, QUERY, STATE, PARAM, MSG> Job for Queries<SETUP, QUERY, STATE, PARAM, MSG>
+where
+    SETUP: Fn(&ArchwayApp) -> STATE + Send + Sync,
+    QUERY: Fn(&ArchwayApp, &STATE, &PARAM) -> QuerySetup<MSG> + 'static + Send + Sync,
+    PARAM: Naming + Send + Sync,
+    MSG: Sized + Serialize + Send + Sync,
+{
+    fn set_group_id(&mut self, id: usize) {
+        self.id = id;
+    }
+
+    fn get_group_id(&self) -> usize {
+        self.id
+    }
+
+    fn run(&self, app: ArchwayApp) -> JobResult {
+        let state = (self.setup)(&app);
+
+        let mut results = vec![];
+        for param in self.parameters.iter() {
+            let bench_name = param.name();
+            let query = (self.query)(&app, &state, param);
+            results.push(bench_query(&app, bench_name, query));
+        }
+
+        JobResult {
+            group_id: self.id,
+            results,
+        }
+    }
+}
+
 fn get_balance_as_aarch(bank: Bank<ArchwayApp>, addr: &SigningAccount) -> u128 {
     bank.query_balance(&QueryBalanceRequest {
         address: addr.address(),
@@ -124,18 +224,120 @@ fn bench_msg<MSG: Sized + Serialize>(
     setup: Setup<MSG>,
 ) -> BenchResult {
     let initial_balance = get_balance_as_aarch(Bank::new(app), &setup.signer);
+    let flat_fee: u128 = app
+        .contract_flat_fee(&setup.contract)
+        .unwrap()
+        .iter()
+        .filter(|c| c.denom == FEE_DENOM)
+        .map(|c| c.amount.u128())
+        .sum();
 
-    let wasm = Wasm::new(app);
-    let res = wasm
-        .execute(&setup.contract, &setup.msg, &setup.funds, &setup.signer)
-        .unwrap();
+    let msg = MsgExecuteContract {
+        sender: setup.signer.address(),
+        contract: setup.contract.clone(),
+        msg: serde_json::to_vec(&setup.msg).unwrap(),
+        funds: setup.funds.iter().map(to_proto).collect(),
+    };
+    let msgs = [(msg, "/cosmwasm.wasm.v1.MsgExecuteContract")];
+    let fee_setting = setup
+        .fee_setting
+        .as_ref()
+        .unwrap_or(setup.signer.fee_setting());
+
+    if setup.simulate {
+        let res = app
+            .simulate_with_fee_setting::<_, MsgExecuteContractResponse>(
+                &msgs,
+                &setup.signer,
+                fee_setting,
+            )
+            .unwrap();
+        let fee: u128 = res
+            .fee
+            .amount
+            .iter()
+            .filter(|c| c.denom.as_ref() == FEE_DENOM)
+            .map(|c| c.amount)
+            .sum();
+
+        return BenchResult {
+            name,
+            gas: Gas {
+                wanted: res.response.gas_info.gas_wanted as u128,
+                used: res.response.gas_info.gas_used as u128,
+                breakdown: GasBreakdown::from_events(&res.response.events).unwrap(),
+            },
+            arch: if setup.granter.is_some() { 0 } else { fee },
+            flat_fee,
+            sponsored: if setup.granter.is_some() { fee } else { 0 },
+            bytes_written: 0,
+        };
+    }
+
+    let state = app.contract_state_snapshot(&setup.contract).unwrap();
+    let mut sponsored = 0;
+    let res = match &setup.granter {
+        Some(granter) => {
+            let res = app
+                .execute_with_contract_granter_and_fee_setting::<_, MsgExecuteContractResponse>(
+                    &msgs,
+                    &setup.signer,
+                    granter,
+                    fee_setting,
+                )
+                .unwrap();
+            sponsored = res.granter_paid;
+            res.response
+        }
+        None => app
+            .execute_multiple_with_fee_setting::<_, MsgExecuteContractResponse>(
+                &msgs,
+                &setup.signer,
+                None,
+                fee_setting,
+            )
+            .unwrap(),
+    };
 
     BenchResult {
         name,
         gas: Gas {
             wanted: res.gas_info.gas_wanted as u128,
             used: res.gas_info.gas_used as u128,
+            breakdown: GasBreakdown::from_events(&res.events).unwrap(),
         },
         arch: initial_balance - get_balance_as_aarch(Bank::new(app), &setup.signer),
+        flat_fee,
+        sponsored,
+        bytes_written: state
+            .diff(&app.contract_state_snapshot(&setup.contract).unwrap())
+            .bytes_written(),
+    }
+}
+
+fn bench_query<MSG: Sized + Serialize>(
+    app: &ArchwayApp,
+    name: String,
+    query: QuerySetup<MSG>,
+) -> BenchResult {
+    let res = app
+        .query_smart_with_gas::<_, serde_json::Value>(
+            &query.contract,
+            &query.msg,
+            Some(query.gas_limit.unwrap_or(DEFAULT_SMART_QUERY_GAS_LIMIT)),
+        )
+        .unwrap();
+
+    BenchResult {
+        name,
+        gas: Gas {
+            wanted: res.gas_limit as u128,
+            used: res.gas_used as u128,
+            breakdown: None,
+        },
+        arch: 0,
+        flat_fee: 0,
+        sponsored: 0,
+        bytes_written: 0,
     }
 }
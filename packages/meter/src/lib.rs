@@ -7,7 +7,7 @@ pub mod results;
 #[cfg(test)]
 mod tests {
     use crate::harness::HarnessBuilder;
-    use crate::job::Setup;
+    use crate::job::{QuerySetup, Setup};
     use std::sync::Arc;
 
     #[test]
@@ -16,12 +16,7 @@ mod tests {
         harness.continuous_group(
             "continuous",
             |_| (),
-            |app, _, _| Setup {
-                contract: "".to_string(),
-                signer: Arc::new(app.init_account(&[]).unwrap()),
-                funds: vec![],
-                msg: (),
-            },
+            |app, _, _| Setup::new("", Arc::new(app.init_account(&[]).unwrap()), vec![], ()),
             vec![0, 1, 2, 3, 4, 5],
         );
 
@@ -30,16 +25,29 @@ mod tests {
 
         harness.independent_group(
             "independent",
-            |app, _| Setup {
-                contract: "".to_string(),
-                signer: Arc::new(app.init_account(&[]).unwrap()),
-                funds: vec![],
-                msg: (),
+            |app, _| {
+                Setup::new("", Arc::new(app.init_account(&[]).unwrap()), vec![], ())
+                    .with_granter("granter")
+                    .simulated()
             },
             vec![0, 1, 2, 3, 4, 5],
         );
 
         assert_eq!(harness.jobs.len(), 7);
         assert_eq!(harness.groups.len(), 2);
+
+        harness.query_group(
+            "queries",
+            |_| "contract".to_string(),
+            |_, contract, _| QuerySetup {
+                contract: contract.clone(),
+                msg: (),
+                gas_limit: None,
+            },
+            vec![0, 1, 2, 3, 4, 5],
+        );
+
+        assert_eq!(harness.jobs.len(), 8);
+        assert_eq!(harness.groups.len(), 3);
     }
 }
@@ -1,4 +1,5 @@
 use crate::naming::NameType;
+use litmus_chain::gas::GasBreakdown;
 use serde::{Deserialize, Serialize};
 
 pub type BenchResults = Vec<BenchResult>;
@@ -15,10 +16,21 @@ pub struct BenchResult {
     pub name: String,
     pub gas: Gas,
     pub arch: u128,
+    /// Portion of the spent aarch that went to the contract's flat fee
+    #[serde(default)]
+    pub flat_fee: u128,
+    /// aarch paid by the granting contract on contract-sponsored txs
+    #[serde(default)]
+    pub sponsored: u128,
+    /// Bytes written to the benched contract's storage, zero for simulated txs
+    #[serde(default)]
+    pub bytes_written: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Gas {
     pub wanted: u128,
     pub used: u128,
+    #[serde(default)]
+    pub breakdown: Option<GasBreakdown>,
 }